@@ -0,0 +1,303 @@
+//! Batch transcription of CSV/TSV tables.
+//!
+//! Selected columns are transcribed into extra columns appended to every
+//! record. Other fields are written back byte for byte, with their original
+//! quoting and line endings.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::orthography::Orthography;
+use crate::overrides::Overrides;
+use crate::registry::Registry;
+use crate::transcriber::{TranscriberEnum, TranscriberTrait};
+
+#[derive(Debug)]
+pub enum Error {
+    UnclosedQuote { line: usize },
+    UnexpectedQuote { line: usize },
+    UnknownColumn(String),
+    UnknownLanguage(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnclosedQuote { line } => {
+                write!(f, "line {line}: quoted field is not closed")
+            }
+            Error::UnexpectedQuote { line } => {
+                write!(f, "line {line}: unexpected text after quoted field")
+            }
+            Error::UnknownColumn(column) => {
+                write!(f, "unknown column '{column}'")
+            }
+            Error::UnknownLanguage(lang) => {
+                write!(f, "unknown language '{lang}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Column by header name or 1-based number
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Name(String),
+    Number(usize),
+}
+
+impl From<&str> for Column {
+    fn from(column: &str) -> Self {
+        match column.parse() {
+            Ok(number) if number > 0 => Column::Number(number),
+            _ => Column::Name(column.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Column::Name(name) => write!(f, "{name}"),
+            Column::Number(number) => write!(f, "{number}"),
+        }
+    }
+}
+
+/// Language of the whole table or of every row
+#[derive(Debug, Clone, PartialEq)]
+pub enum Language {
+    /// Language name or code
+    Fixed(String),
+    /// Column holding language name or code of the row
+    Column(Column),
+    /// Detected from the transcribed columns of every row
+    Auto,
+}
+
+/// Batch job settings
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub columns: Vec<Column>,
+    pub language: Language,
+    /// Field delimiter (`,` for CSV, `\t` for TSV)
+    pub delimiter: char,
+    /// First record names the columns
+    pub header: bool,
+    /// Spelling conventions of the transcriptions
+    pub orthography: Orthography,
+}
+
+/// Transcribed table
+#[derive(Debug, Clone, PartialEq)]
+pub struct Batch {
+    pub output: String,
+    /// Rows (1-based, header included) with unknown or undetected (empty)
+    /// language, left empty
+    pub skipped: Vec<(usize, String)>,
+}
+
+// Field as written in the source and its unquoted value
+struct Field<'a> {
+    raw: &'a str,
+    value: Cow<'a, str>,
+}
+
+struct Record<'a> {
+    fields: Vec<Field<'a>>,
+    // Line ending ("\n", "\r\n" or none at the end of input)
+    end: &'a str,
+}
+
+/// Transcribes selected columns of table as the GUI does
+pub fn transcribe(
+    registry: &Registry,
+    overrides: &Overrides,
+    options: &Options,
+    input: &str,
+) -> Result<Batch, Error> {
+    let records = parse(input, options.delimiter)?;
+    let header = records.first().filter(|_| options.header);
+
+    let resolve = |column: &Column| match column {
+        Column::Number(number) => Ok(number - 1),
+        Column::Name(name) => header
+            .and_then(|header| {
+                header.fields.iter().position(|field| field.value == *name)
+            })
+            .ok_or_else(|| Error::UnknownColumn(name.clone())),
+    };
+    let columns =
+        options.columns.iter().map(resolve).collect::<Result<Vec<_>, _>>()?;
+
+    enum Source<'a> {
+        Fixed(&'a TranscriberEnum),
+        Column(usize),
+        Auto,
+    }
+    let source = match &options.language {
+        Language::Fixed(lang) => Source::Fixed(
+            registry
+                .find(lang)
+                .ok_or_else(|| Error::UnknownLanguage(lang.clone()))?,
+        ),
+        Language::Column(column) => Source::Column(resolve(column)?),
+        Language::Auto => Source::Auto,
+    };
+
+    // Short records are padded so that new columns stay aligned
+    let width = records.first().map_or(0, |record| record.fields.len());
+
+    let mut batch = Batch {
+        output: String::with_capacity(input.len() * 2),
+        skipped: Vec::new(),
+    };
+    for (i, record) in records.iter().enumerate() {
+        let value = |column: usize| {
+            record.fields.get(column).map_or("", |field| &field.value)
+        };
+
+        let cells: Vec<String> = if i == 0 && options.header {
+            columns.iter().map(|&c| format!("{}_ru", value(c))).collect()
+        } else {
+            let (transcriber, lang) = match source {
+                Source::Fixed(transcriber) => (Some(transcriber), ""),
+                Source::Column(column) => {
+                    let lang = value(column).trim();
+                    (registry.find(lang), lang)
+                }
+                Source::Auto => {
+                    let text: Vec<_> =
+                        columns.iter().map(|&c| value(c)).collect();
+                    let detections = registry.detect(&text.join(" "));
+                    let best =
+                        detections.first().filter(|d| d.confidence > 0.0);
+                    (best.map(|d| d.transcriber), "")
+                }
+            };
+            if transcriber.is_none() {
+                batch.skipped.push((i + 1, lang.to_string()));
+            }
+            columns
+                .iter()
+                .map(|&c| match transcriber {
+                    Some(t) => {
+                        t.trace_in(value(c), overrides, &options.orthography)
+                            .output
+                    }
+                    None => String::new(),
+                })
+                .collect()
+        };
+
+        let delimiter = options.delimiter;
+        for (j, field) in record.fields.iter().enumerate() {
+            if j > 0 {
+                batch.output.push(delimiter);
+            }
+            batch.output.push_str(field.raw);
+        }
+        for _ in record.fields.len()..width {
+            batch.output.push(delimiter);
+        }
+        for cell in &cells {
+            batch.output.push(delimiter);
+            batch.output.push_str(&quote(cell, delimiter));
+        }
+        batch.output.push_str(record.end);
+    }
+
+    Ok(batch)
+}
+
+fn parse(input: &str, delimiter: char) -> Result<Vec<Record<'_>>, Error> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut line = 1;
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        // Field
+        let (field, tail) = if let Some(quoted) = rest.strip_prefix('"') {
+            let close =
+                closing_quote(quoted).ok_or(Error::UnclosedQuote { line })?;
+            let raw = &rest[..close + 2];
+            let value = &quoted[..close];
+            line += value.matches('\n').count();
+            let value = match value.contains("\"\"") {
+                true => Cow::Owned(value.replace("\"\"", "\"")),
+                false => Cow::Borrowed(value),
+            };
+            (Field { raw, value }, &rest[raw.len()..])
+        } else {
+            let len = rest.find([delimiter, '\n']).unwrap_or(rest.len());
+            let raw = &rest[..len];
+            let raw = match rest[len..].starts_with('\n') {
+                true => raw.strip_suffix('\r').unwrap_or(raw),
+                false => raw,
+            };
+            let value = Cow::Borrowed(raw);
+            (Field { raw, value }, &rest[raw.len()..])
+        };
+        fields.push(field);
+
+        // Delimiter, line ending or end of input
+        if let Some(tail) = tail.strip_prefix(delimiter) {
+            rest = tail;
+            // Empty last field ends the last record
+            if rest.is_empty() {
+                fields.push(Field {
+                    raw: "",
+                    value: Cow::Borrowed(""),
+                });
+                records.push(Record {
+                    fields: std::mem::take(&mut fields),
+                    end: "",
+                });
+            }
+            continue;
+        }
+        let end_len = if tail.starts_with("\r\n") {
+            2
+        } else if tail.starts_with('\n') || tail.is_empty() {
+            tail.len().min(1)
+        } else {
+            return Err(Error::UnexpectedQuote { line });
+        };
+        records.push(Record {
+            fields: std::mem::take(&mut fields),
+            end: &tail[..end_len],
+        });
+        line += 1;
+        rest = &tail[end_len..];
+    }
+
+    Ok(records)
+}
+
+// Position of the quote closing field (doubled quotes are escaped)
+fn closing_quote(quoted: &str) -> Option<usize> {
+    let bytes = quoted.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'"' {
+            if bytes.get(i + 1) != Some(&b'"') {
+                return Some(i);
+            }
+            i += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+// Quotes field only if necessary
+fn quote(field: &str, delimiter: char) -> Cow<'_, str> {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
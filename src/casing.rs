@@ -1,6 +1,8 @@
+use std::ops::Range;
+
 use unicode_segmentation::UnicodeSegmentation;
 
-#[derive(PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Case {
     Lower,
     Title,
@@ -11,9 +13,31 @@ pub enum Case {
 /// Converts string to Title
 fn titleize(word: &str) -> String {
     let lower = word.to_lowercase();
-    let mut graphemes = lower.graphemes(true);
-    let first = graphemes.next().unwrap();
-    first.to_uppercase() + graphemes.as_str()
+    let first = lower.graphemes(true).next().unwrap_or_default();
+    let mut title = String::with_capacity(lower.len() + 4);
+    let mut upper = first.chars().flat_map(char::to_uppercase);
+    title.extend(upper.next());
+    // Capitals spelled with several letters keep one (ᾠ → Ὠι)
+    title.extend(upper.flat_map(char::to_lowercase));
+    title += &lower[first.len()..];
+    title
+}
+
+/// Uppercases word, mapping every uppercased byte to its original offset
+/// (with one extra entry for the word end)
+pub fn uppercase(word: &str) -> (String, Vec<usize>) {
+    let mut upper = String::with_capacity(word.len());
+    let mut offsets = Vec::with_capacity(word.len() + 1);
+
+    for (i, c) in word.char_indices() {
+        for u in c.to_uppercase() {
+            upper.push(u);
+        }
+        offsets.resize(upper.len(), i);
+    }
+    offsets.push(word.len());
+
+    (upper, offsets)
 }
 
 /// Reapplies original word case
@@ -25,12 +49,40 @@ pub fn reapply_case(word: &str, case: Case) -> String {
     }
 }
 
+/// Records case of the word fragment at range: a lone capital is upper case
+/// next to other capitals and title case otherwise
+pub fn record_fragment_case(word: &str, range: Range<usize>) -> Case {
+    let fragment = &word[range.clone()];
+    let upper = fragment.chars().filter(|c| c.is_uppercase()).count();
+    let lower = fragment.chars().filter(|c| c.is_lowercase()).count();
+
+    match (upper, lower) {
+        (0, _) => Case::Lower,
+        (1, 0) => {
+            let is_cased = |c: &char| c.is_uppercase() || c.is_lowercase();
+            let before = word[..range.start].chars().rev().find(is_cased);
+            let after = word[range.end..].chars().find(is_cased);
+            if before.is_some_and(char::is_uppercase)
+                || after.is_some_and(char::is_uppercase)
+            {
+                Case::Upper
+            } else {
+                Case::Title
+            }
+        }
+        (_, 0) => Case::Upper,
+        _ => record_case(fragment),
+    }
+}
+
 /// Records original word case
 pub fn record_case(input: &str) -> Case {
     let mut chars = input.chars();
 
-    // Get first character for sure
-    let first = chars.next().unwrap();
+    // Empty input has nothing to uppercase
+    let Some(first) = chars.next() else {
+        return Case::Lower;
+    };
 
     // Make first assumption for case
     if first.is_lowercase() {
@@ -63,8 +115,6 @@ pub fn record_case(input: &str) -> Case {
             Case::Mixed
         } else if has_uppercase {
             Case::Upper
-        } else if has_lowercase {
-            Case::Title
         } else {
             Case::Title
         }
@@ -0,0 +1,429 @@
+use std::fs;
+use std::io::{self, BufRead, Read, Write};
+use std::process::ExitCode;
+
+use cyrillify::batch::{self, Column, Language, Options};
+use cyrillify::detect::Detection;
+use cyrillify::orthography::{AfterVowel, Orthography};
+use cyrillify::{Origin, Registry, Trace, TranscriberEnum, TranscriberTrait};
+use cyrillify::{coverage, custom, name, spelling, stress, validate};
+
+use crate::config;
+
+// Language detected from input
+const AUTO: &str = "auto";
+
+const USAGE: &str = "\
+Usage:
+  cyrillify                    start the application (if built with it)
+  cyrillify languages          list languages (code and name)
+  cyrillify validate [RULE_FILE]...
+                               check rule tables (default: all languages)
+  cyrillify coverage [LANG]... list rules no test mapping uses, by section
+                               (default: all languages)
+  cyrillify transcribe [OPTIONS] [TEXT]...
+      -l, --lang <LANG>        language name or code (default: auto)
+      -s, --stress             mark stressed vowels (combining acute)
+      -f, --fields             print family and given names separated by
+                               a tab (honorifics dropped)
+      -e, --explain            print the language and the rule behind
+                               every fragment
+  cyrillify detect [TEXT]...   print the most likely language and its
+                               confidence
+  TEXT defaults to stdin, one name per line
+  cyrillify batch [OPTIONS] [INPUT]
+      --column <COLUMN>        column to transcribe (name or number),
+                               may be repeated
+      --lang <LANG>            language name or code of the table
+                               (auto: detected row by row)
+      --lang-column <COLUMN>   column with language of every row
+      --csv | --tsv            table format (default: by INPUT extension)
+      --no-header              first row holds data, not column names
+      -o, --output <FILE>      output file (default: stdout)
+  INPUT defaults to stdin; transcriptions are appended as new columns
+Spelling options (transcribe and batch):
+      --no-yo                  write Е instead of Ё
+      --after-vowel <short|full>
+                               write Й (short) or И (full) after vowels
+      --no-final-soft-sign     drop Ь at word end
+      --replace <FROM=TO>      replace letter sequence, may be repeated";
+
+/// Runs command given by arguments
+pub fn run(args: &[String]) -> ExitCode {
+    let Some(command) = args.first() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+
+    let result = match command.as_str() {
+        "languages" => run_languages(),
+        "validate" => run_validate(&args[1..]),
+        "coverage" => run_coverage(&args[1..]),
+        "transcribe" => run_transcribe(&args[1..]),
+        "detect" => run_detect(&args[1..]),
+        "batch" => run_batch(&args[1..]),
+        "-h" | "--help" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        command => Err(format!("unknown command '{command}'")),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("cyrillify: {err}\nTry 'cyrillify --help'");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_languages() -> Result<(), String> {
+    let registry = config::load_registry();
+    let mut stdout = io::stdout().lock();
+    for transcriber in registry.iter() {
+        let code = transcriber.get_lang_code();
+        let name = transcriber.get_lang_name();
+        writeln!(stdout, "{code}\t{name}").map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+fn run_validate(args: &[String]) -> Result<(), String> {
+    // Rule files given, all languages otherwise
+    let transcribers: Vec<TranscriberEnum> = if args.is_empty() {
+        config::load_registry().into_iter().collect()
+    } else {
+        let mut transcribers = Vec::new();
+        for path in args {
+            let transcriber = custom::Transcriber::load(path)
+                .map_err(|err| format!("{path}: {err}"))?;
+            transcribers.push(transcriber.into());
+        }
+        transcribers
+    };
+
+    let mut failed = 0;
+    for transcriber in &transcribers {
+        let report = validate::report(transcriber);
+        if !report.is_empty() {
+            print!("{report}");
+            failed += 1;
+        }
+    }
+
+    match failed {
+        0 => Ok(()),
+        n => Err(format!("rules of {n} language(s) have issues")),
+    }
+}
+
+fn run_coverage(args: &[String]) -> Result<(), String> {
+    let registry = config::load_registry();
+    let transcribers: Vec<&TranscriberEnum> = if args.is_empty() {
+        registry.iter().collect()
+    } else {
+        let mut transcribers = Vec::new();
+        for lang in args {
+            let transcriber = registry
+                .find(lang)
+                .ok_or_else(|| format!("unknown language '{lang}'"))?;
+            transcribers.push(transcriber);
+        }
+        transcribers
+    };
+
+    for transcriber in transcribers {
+        print!("{}", coverage::report(transcriber));
+    }
+    Ok(())
+}
+
+fn run_transcribe(args: &[String]) -> Result<(), String> {
+    let mut lang = None;
+    let mut stressed = false;
+    let mut fields = false;
+    let mut explain = false;
+    let mut orthography = Orthography::default();
+    let mut words = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if orthography_option(arg, &mut args, &mut orthography)? {
+            continue;
+        }
+        match arg.as_str() {
+            "-l" | "--lang" => {
+                let value = args.next();
+                lang = Some(value.ok_or("'--lang' requires a value")?);
+            }
+            "-s" | "--stress" => stressed = true,
+            "-f" | "--fields" => fields = true,
+            "-e" | "--explain" => explain = true,
+            word if !word.starts_with('-') => words.push(word),
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    let registry = config::load_registry();
+    let fixed = match lang.map(String::as_str) {
+        None | Some(AUTO) => None,
+        Some(lang) => Some(
+            registry
+                .find(lang)
+                .ok_or_else(|| format!("unknown language '{lang}'"))?,
+        ),
+    };
+    let overrides = config::load_overrides();
+
+    let mut stdout = io::stdout().lock();
+    let mut undetected = 0;
+    for_each_name(&words, |name| {
+        let transcriber = match fixed {
+            Some(transcriber) => transcriber,
+            None => match detect(&registry, name) {
+                Some(detection) => {
+                    if explain {
+                        write_detection(&mut stdout, &detection)?;
+                    }
+                    detection.transcriber
+                }
+                // Lines stay aligned with input
+                None => {
+                    eprintln!("'{name}': language not detected");
+                    undetected += 1;
+                    return writeln!(stdout);
+                }
+            },
+        };
+
+        // Warnings on the output as printed (spelling options applied)
+        let trace = transcriber.trace_in(name, &overrides, &orthography);
+        for warning in spelling::check(&trace) {
+            eprintln!("'{name}': {warning}");
+        }
+        // User overrides are spelled as wanted
+        let stress = if stressed && !trace.overridden() {
+            transcriber.get_name_convention().stress
+        } else {
+            stress::Stress::None
+        };
+        if fields {
+            let name =
+                name::parse(transcriber, name, &overrides, &orthography);
+            let family = name.family().unwrap_or_default();
+            let family = stress::stress_text(family, stress);
+            let given = stress::stress_text(&name.given(), stress);
+            writeln!(stdout, "{family}\t{given}")?;
+        } else {
+            let output = stress::stress_text(&trace.output, stress);
+            writeln!(stdout, "{output}")?;
+        }
+        if explain {
+            write_explanation(&mut stdout, name, &trace)?;
+        }
+        Ok(())
+    })?;
+
+    match undetected {
+        0 => Ok(()),
+        n => Err(format!("language of {n} name(s) not detected")),
+    }
+}
+
+fn run_detect(args: &[String]) -> Result<(), String> {
+    let words: Vec<_> = args.iter().map(String::as_str).collect();
+    if let Some(arg) = words.iter().find(|word| word.starts_with('-')) {
+        return Err(format!("unexpected argument '{arg}'"));
+    }
+
+    let registry = config::load_registry();
+    let mut stdout = io::stdout().lock();
+    for_each_name(&words, |name| match detect(&registry, name) {
+        Some(detection) => write_detection(&mut stdout, &detection),
+        None => writeln!(stdout, "-\t-\t0%"),
+    })
+}
+
+// Best detection, if any language is typical of the name at all
+fn detect<'a>(registry: &'a Registry, name: &str) -> Option<Detection<'a>> {
+    let detections = registry.detect(name);
+    detections.into_iter().next().filter(|d| d.confidence > 0.0)
+}
+
+fn write_detection(
+    out: &mut impl Write,
+    detection: &Detection,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "{}\t{}\t{:.0}%",
+        detection.transcriber.get_lang_code(),
+        detection.transcriber.get_lang_name(),
+        detection.confidence * 100.0,
+    )
+}
+
+// Arguments form a single name, stdin one name per line
+fn for_each_name(
+    words: &[&str],
+    mut f: impl FnMut(&str) -> io::Result<()>,
+) -> Result<(), String> {
+    if !words.is_empty() {
+        return f(&words.join(" ")).map_err(|err| err.to_string());
+    }
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|err| err.to_string())?;
+        f(&line).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+// Source fragment, its output and the rule behind it, one per line
+fn write_explanation(
+    out: &mut impl Write,
+    input: &str,
+    trace: &Trace,
+) -> io::Result<()> {
+    for segment in &trace.segments {
+        let origin = match segment.origin {
+            Origin::Rule(rule) => rule.key,
+            Origin::Exception(_) => "(exception)",
+            Origin::Override => "(override)",
+            Origin::Unmatched => "(unmatched)",
+            Origin::Separator => continue,
+        };
+        let source = &input[segment.source.clone()];
+        let target = trace.fragment(segment);
+        writeln!(out, "  {source:<10} {target:<10} {origin}")?;
+    }
+    Ok(())
+}
+
+// Applies spelling option, returns false if arg is not one
+fn orthography_option(
+    arg: &str,
+    args: &mut std::slice::Iter<String>,
+    orthography: &mut Orthography,
+) -> Result<bool, String> {
+    let mut value = || {
+        args.next()
+            .map(String::as_str)
+            .ok_or_else(|| format!("'{arg}' requires a value"))
+    };
+    match arg {
+        "--no-yo" => orthography.fold_yo = true,
+        "--after-vowel" => {
+            orthography.after_vowel = match value()? {
+                "short" => AfterVowel::Short,
+                "full" => AfterVowel::Full,
+                value => {
+                    return Err(format!("unknown '--after-vowel' '{value}'"));
+                }
+            }
+        }
+        "--no-final-soft-sign" => orthography.drop_final_soft_sign = true,
+        "--replace" => {
+            let value = value()?;
+            let (from, to) = value.split_once('=').ok_or_else(|| {
+                format!("'--replace' expects FROM=TO, got '{value}'")
+            })?;
+            orthography.replacements.push((from.into(), to.into()));
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn run_batch(args: &[String]) -> Result<(), String> {
+    let mut columns = Vec::new();
+    let mut language = None;
+    let mut delimiter = None;
+    let mut header = true;
+    let mut input = None;
+    let mut output = None;
+    let mut orthography = Orthography::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if orthography_option(arg, &mut args, &mut orthography)? {
+            continue;
+        }
+        let mut value = || {
+            args.next()
+                .map(String::as_str)
+                .ok_or_else(|| format!("'{arg}' requires a value"))
+        };
+        match arg.as_str() {
+            "--column" => columns.push(Column::from(value()?)),
+            "--lang" => {
+                language = Some(match value()? {
+                    AUTO => Language::Auto,
+                    lang => Language::Fixed(lang.into()),
+                })
+            }
+            "--lang-column" => {
+                language = Some(Language::Column(value()?.into()))
+            }
+            "--csv" => delimiter = Some(','),
+            "--tsv" => delimiter = Some('\t'),
+            "--no-header" => header = false,
+            "-o" | "--output" => output = Some(value()?),
+            path if !path.starts_with('-') && input.is_none() => {
+                input = Some(path)
+            }
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    if columns.is_empty() {
+        return Err("no column to transcribe (--column)".into());
+    }
+    let language = language.ok_or("no language (--lang or --lang-column)")?;
+    let delimiter = delimiter.unwrap_or(match input {
+        Some(path) if path.ends_with(".tsv") => '\t',
+        _ => ',',
+    });
+
+    let source = match input {
+        Some(path) => {
+            fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?
+        }
+        None => {
+            let mut source = String::new();
+            io::stdin()
+                .read_to_string(&mut source)
+                .map_err(|err| err.to_string())?;
+            source
+        }
+    };
+
+    let options = Options {
+        columns,
+        language,
+        delimiter,
+        header,
+        orthography,
+    };
+    let registry = config::load_registry();
+    let overrides = config::load_overrides();
+    let result = batch::transcribe(&registry, &overrides, &options, &source)
+        .map_err(|err| err.to_string())?;
+
+    for (row, lang) in &result.skipped {
+        match lang.as_str() {
+            "" => eprintln!("row {row}: language not detected, left empty"),
+            lang => {
+                eprintln!("row {row}: unknown language '{lang}', left empty")
+            }
+        }
+    }
+
+    match output {
+        Some(path) => fs::write(path, result.output)
+            .map_err(|err| format!("{path}: {err}")),
+        None => io::stdout()
+            .write_all(result.output.as_bytes())
+            .map_err(|err| err.to_string()),
+    }
+}
@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use cyrillify::{Overrides, Registry};
+
+// <config dir>/cyrillify
+fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("cyrillify"))
+}
+
+/// Built-in languages plus user rule files (<config dir>/cyrillify/langs)
+pub fn load_registry() -> Registry {
+    let mut registry = Registry::default();
+    if let Some(config_dir) = config_dir() {
+        for (path, result) in registry.load_dir(config_dir.join("langs")) {
+            if let Err(err) = result {
+                eprintln!("{}: {}", path.display(), err);
+            }
+        }
+    }
+    registry
+}
+
+/// User overrides file (<config dir>/cyrillify/overrides.toml)
+pub fn overrides_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("overrides.toml"))
+}
+
+/// Loads user overrides (unreadable ones are reported and skipped)
+pub fn load_overrides() -> Overrides {
+    let Some(path) = overrides_path() else {
+        return Overrides::default();
+    };
+    Overrides::load(&path).unwrap_or_else(|err| {
+        eprintln!("{}: {}", path.display(), err);
+        Overrides::default()
+    })
+}
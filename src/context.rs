@@ -0,0 +1,111 @@
+//! Context conditions of rules.
+//!
+//! A rule key may carry a condition after `/`, written as in phonology:
+//! `"P / _[AEIOU]"` reads "P before a vowel", `"S / [AEIOU]_|"` reads
+//! "S between a vowel and the word end". The context is checked around the
+//! match but never consumed. Context elements are:
+//!
+//! * a literal character, e.g. `A`;
+//! * a character class, e.g. `[AEIOU]`, or a negated one, e.g. `[^AEIOU]`;
+//! * `|` for the word boundary.
+
+/// Splits key into matched part and context (if any)
+pub const fn split(key: &str) -> (&str, Option<&str>) {
+    let bytes = key.as_bytes();
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'/' {
+            let (body, context) = key.split_at(i);
+            let (_, context) = context.split_at(1);
+            return (trim_end(body), Some(context));
+        }
+        i += 1;
+    }
+
+    (key, None)
+}
+
+const fn trim_end(string: &str) -> &str {
+    let mut string = string;
+    while let Some((last, rest)) = string.as_bytes().split_last() {
+        if *last != b' ' {
+            break;
+        }
+        (string, _) = string.split_at(rest.len());
+    }
+    string
+}
+
+/// Checks context around word span `start..end`
+pub fn matches(context: &str, word: &str, start: usize, end: usize) -> bool {
+    let (left, right) = context.split_once('_').unwrap_or(("", context));
+
+    // Left context is matched backwards from the span start
+    let mut before = word[..start].chars().rev();
+    let left_ok = Elements(left.trim())
+        .rev()
+        .all(|element| element_matches(element, &mut before));
+
+    let mut after = word[end..].chars();
+    left_ok
+        && Elements(right.trim())
+            .all(|element| element_matches(element, &mut after))
+}
+
+fn element_matches(
+    element: &str,
+    chars: &mut (impl Iterator<Item = char> + Clone),
+) -> bool {
+    // Word boundary consumes nothing
+    if element == "|" {
+        return chars.clone().next().is_none();
+    }
+
+    let Some(c) = chars.next() else {
+        return false;
+    };
+
+    match element.strip_prefix('[').and_then(|e| e.strip_suffix(']')) {
+        Some(class) => match class.strip_prefix('^') {
+            Some(negated) => !negated.contains(c),
+            None => class.contains(c),
+        },
+        None => element.chars().eq([c]),
+    }
+}
+
+// Context elements: characters, `[...]` classes and `|` boundaries
+struct Elements<'a>(&'a str);
+
+impl<'a> Iterator for Elements<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.0 = self.0.trim_start();
+        let len = if self.0.starts_with('[') {
+            self.0.find(']').map_or(self.0.len(), |i| i + 1)
+        } else {
+            self.0.chars().next()?.len_utf8()
+        };
+
+        let (element, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(element)
+    }
+}
+
+impl DoubleEndedIterator for Elements<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0 = self.0.trim_end();
+        let start = if self.0.ends_with(']') {
+            self.0.rfind('[').unwrap_or(0)
+        } else {
+            self.0.len() - self.0.chars().next_back()?.len_utf8()
+        };
+
+        let (rest, element) = self.0.split_at(start);
+        self.0 = rest;
+        Some(element)
+    }
+}
@@ -0,0 +1,151 @@
+//! Rule coverage of the test mappings.
+//!
+//! Every mapping of a language is run through the engine and the rules,
+//! post rules and exceptions behind its output are recorded, so the ones
+//! no test name exercises can be listed by section.
+
+use std::collections::HashSet;
+
+use crate::dictionary::Entry;
+use crate::trace::Origin;
+use crate::transcriber::{Rule, TranscriberTrait};
+
+/// Rules, post rules and exceptions no mapping uses, in table order
+pub struct Coverage {
+    pub rules: usize,
+    pub unused: Vec<&'static Rule>,
+    pub post_rules: usize,
+    pub unused_post_rules: Vec<&'static Rule>,
+    pub exceptions: usize,
+    pub unused_exceptions: Vec<&'static Entry>,
+}
+
+impl Coverage {
+    /// Share of rules used by the mappings
+    pub fn ratio(&self) -> f32 {
+        match self.rules {
+            0 => 1.0,
+            rules => (rules - self.unused.len()) as f32 / rules as f32,
+        }
+    }
+}
+
+/// Runs every mapping of transcriber and collects what it leaves unused
+pub fn coverage<T: TranscriberTrait + ?Sized>(transcriber: &T) -> Coverage {
+    // Rules are identified by address, equal keys may repeat
+    let mut used: HashSet<*const Rule> = HashSet::new();
+    let mut used_post_rules: HashSet<*const Rule> = HashSet::new();
+    let mut used_exceptions: HashSet<*const Entry> = HashSet::new();
+    for mapping in transcriber.get_mappings() {
+        let trace = transcriber.trace(mapping.from);
+        for segment in &trace.segments {
+            match segment.origin {
+                Origin::Rule(rule) => {
+                    used.insert(rule);
+                }
+                Origin::Exception(entry) => {
+                    used_exceptions.insert(entry);
+                }
+                _ => {}
+            }
+        }
+        used_post_rules
+            .extend(trace.post_rules.iter().map(|&r| r as *const _));
+    }
+
+    let rules = transcriber.get_rules();
+    let post_rules = transcriber.get_post_rules();
+    let exceptions = transcriber.get_exceptions();
+    Coverage {
+        rules: rules.len(),
+        unused: rules.iter().filter(|&r| !used.contains(&(r as _))).collect(),
+        post_rules: post_rules.len(),
+        unused_post_rules: post_rules
+            .iter()
+            .filter(|&r| !used_post_rules.contains(&(r as _)))
+            .collect(),
+        exceptions: exceptions.len(),
+        unused_exceptions: exceptions
+            .iter()
+            .filter(|&e| !used_exceptions.contains(&(e as _)))
+            .collect(),
+    }
+}
+
+/// Report of the coverage of transcriber rules by section
+pub fn report<T: TranscriberTrait + ?Sized>(transcriber: &T) -> String {
+    let coverage = coverage(transcriber);
+    let mut report = format!(
+        "{} ({}): {}/{} rules ({:.0}%)\n",
+        transcriber.get_lang_name(),
+        transcriber.get_lang_code(),
+        coverage.rules - coverage.unused.len(),
+        coverage.rules,
+        coverage.ratio() * 100.0,
+    );
+
+    // Sections in table order
+    let mut sections: Vec<&str> = Vec::new();
+    for rule in transcriber.get_rules() {
+        if !sections.contains(&rule.section) {
+            sections.push(rule.section);
+        }
+    }
+    for section in sections {
+        let total = transcriber
+            .get_rules()
+            .iter()
+            .filter(|rule| rule.section == section)
+            .count();
+        let unused: Vec<_> = coverage
+            .unused
+            .iter()
+            .filter(|rule| rule.section == section)
+            .map(|rule| format!("'{}'", rule.key))
+            .collect();
+        let name = match section {
+            "" => "(no section)",
+            section => section,
+        };
+        report += &format!("  {name}: {}/{total}", total - unused.len());
+        if !unused.is_empty() {
+            report += &format!(", unused: {}", unused.join(", "));
+        }
+        report += "\n";
+    }
+
+    if coverage.post_rules > 0 {
+        let unused: Vec<_> = coverage
+            .unused_post_rules
+            .iter()
+            .map(|rule| format!("'{}'", rule.key))
+            .collect();
+        report += &format!(
+            "  post rules: {}/{}",
+            coverage.post_rules - unused.len(),
+            coverage.post_rules,
+        );
+        if !unused.is_empty() {
+            report += &format!(", unused: {}", unused.join(", "));
+        }
+        report += "\n";
+    }
+
+    if coverage.exceptions > 0 {
+        let unused: Vec<_> = coverage
+            .unused_exceptions
+            .iter()
+            .map(|entry| format!("'{}'", entry.from))
+            .collect();
+        report += &format!(
+            "  exceptions: {}/{}",
+            coverage.exceptions - unused.len(),
+            coverage.exceptions,
+        );
+        if !unused.is_empty() {
+            report += &format!(", unused: {}", unused.join(", "));
+        }
+        report += "\n";
+    }
+    report
+}
@@ -0,0 +1,374 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::dictionary::Entry;
+use crate::name::{Convention, Gender, Order};
+use crate::normalize;
+use crate::pipeline::{self, Stage};
+use crate::stress::Stress;
+use crate::transcriber::{Mapping, Rule, TranscriberTrait};
+use crate::trie::{self, Trie, TrieBuf};
+
+// Rule file layout (TOML):
+//
+//     lang_name = "Тайский"
+//     lang_code = "tha"
+//     markers = ["SAWAT", "WATRA|"]
+//     stages = ["normalize", "exceptions", "rules", "casing"]
+//
+//     [lang_rules]
+//     "PH" = "ПХ"
+//     "|SR" = "С"
+//     "CH|" = "Т"
+//     "CHI" = ["ТИ", "ШИ"]
+//
+//     [lang_rules.Vowels]
+//     "AE" = "Э"
+//
+//     [post_rules]
+//     "|ЬЕ" = "Е"
+//
+//     [exceptions]
+//     "Prayut Chan-o-cha" = "Прают Чан-Оча"
+//
+//     [names]
+//     order = "family-last"
+//     honorifics = ["Khun", "Nai"]
+//     genders = { "Nai" = "male", "Nangsao" = "female" }
+//     stress = "final"
+//
+//     [[mappings]]
+//     from = "Chuan Leekpai"
+//     to = "Чуан Ликпхай"
+#[derive(Deserialize)]
+struct RuleFile {
+    lang_name: String,
+    lang_code: String,
+    lang_rules: IndexMap<String, RuleFileValue>,
+    #[serde(default)]
+    post_rules: IndexMap<String, String>,
+    #[serde(default)]
+    exceptions: IndexMap<String, String>,
+    #[serde(default)]
+    markers: Vec<String>,
+    #[serde(default)]
+    names: RuleFileNames,
+    #[serde(default)]
+    stages: Vec<Stage>,
+    #[serde(default)]
+    mappings: Vec<RuleFileMapping>,
+}
+
+// Single reading, readings with alternatives: ["ТИ", "ШИ"], or a section
+// table of rules: [lang_rules.Consonants]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RuleFileValue {
+    One(String),
+    Many(Vec<String>),
+    Section(IndexMap<String, RuleFileValue>),
+}
+
+// Order: "family-first", "family-last" (default) or "given-only";
+// stress: "none" (default), "final" or "every-syllable"
+#[derive(Deserialize, Default)]
+struct RuleFileNames {
+    #[serde(default)]
+    order: Order,
+    #[serde(default)]
+    honorifics: Vec<String>,
+    #[serde(default)]
+    genders: IndexMap<String, Gender>,
+    #[serde(default)]
+    stress: Stress,
+}
+
+#[derive(Deserialize)]
+struct RuleFileMapping {
+    from: String,
+    to: String,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    EmptyKey,
+    EmptyValue,
+    TooLarge,
+    StageOrder,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "cannot read rule file: {err}"),
+            Error::Parse(err) => write!(f, "malformed rule file: {err}"),
+            Error::EmptyKey => write!(f, "rule file contains an empty key"),
+            Error::EmptyValue => {
+                write!(f, "rule file contains a rule without readings")
+            }
+            Error::TooLarge => write!(f, "rule file has too many rules"),
+            Error::StageOrder => {
+                write!(f, "rule file lists stages out of order")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Parse(err)
+    }
+}
+
+/// Transcriber built at runtime from a rule file.
+///
+/// Rule files are loaded once per process, so their strings are leaked to
+/// share the `'static` representation of the compiled rule tables.
+pub struct Transcriber {
+    lang_name: &'static str,
+    lang_code: &'static str,
+    trie: TrieBuf,
+    post_trie: TrieBuf,
+    exceptions: &'static [Entry],
+    markers: &'static [&'static str],
+    names: Convention,
+    stages: &'static [Stage],
+    mappings: Vec<Mapping<'static>>,
+}
+
+impl Transcriber {
+    /// Builds transcriber from rule table, post rules, exceptions, markers,
+    /// naming convention and test mappings
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_rules(
+        lang_name: &'static str,
+        lang_code: &'static str,
+        rules: &'static [Rule],
+        post_rules: &'static [Rule],
+        exceptions: &'static [Entry],
+        markers: &'static [&'static str],
+        names: Convention,
+        mappings: Vec<Mapping<'static>>,
+    ) -> Result<Self, Error> {
+        if rules.iter().chain(post_rules).any(|rule| rule.key.is_empty())
+            || exceptions.iter().any(|entry| entry.from.is_empty())
+        {
+            return Err(Error::EmptyKey);
+        }
+        if !trie::fits(rules) || !trie::fits(post_rules) {
+            return Err(Error::TooLarge);
+        }
+
+        Ok(Self {
+            lang_name,
+            lang_code,
+            trie: TrieBuf::new(rules),
+            post_trie: TrieBuf::new(post_rules),
+            exceptions,
+            markers,
+            names,
+            stages: pipeline::DEFAULT,
+            mappings,
+        })
+    }
+
+    /// Parses rule file contents (keys are normalized and uppercased like
+    /// input, rules keep file order)
+    pub fn from_toml(source: &str) -> Result<Self, Error> {
+        let file: RuleFile = toml::from_str(source)?;
+
+        let mut rules = Vec::with_capacity(file.lang_rules.len());
+        push_rules(&mut rules, "", file.lang_rules)?;
+        let rules = rules.leak();
+        // Post rules match the uppercased output
+        let post_rules = file
+            .post_rules
+            .into_iter()
+            .map(|(key, value)| Rule {
+                key: key_of(&key),
+                value: leak(value.to_uppercase()),
+                alternatives: &[],
+                section: "POST RULES",
+            })
+            .collect::<Vec<_>>()
+            .leak();
+        let exceptions = file
+            .exceptions
+            .into_iter()
+            .map(|(from, to)| Entry {
+                from: leak(normalize::normalize(&from).text),
+                to: leak(to),
+            })
+            .collect::<Vec<_>>()
+            .leak();
+        let markers = file
+            .markers
+            .into_iter()
+            .map(|marker| key_of(&marker))
+            .collect::<Vec<_>>()
+            .leak();
+        let names = Convention {
+            order: file.names.order,
+            honorifics: file
+                .names
+                .honorifics
+                .into_iter()
+                .map(|honorific| key_of(&honorific))
+                .collect::<Vec<_>>()
+                .leak(),
+            genders: file
+                .names
+                .genders
+                .into_iter()
+                .map(|(part, gender)| (key_of(&part), gender))
+                .collect::<Vec<_>>()
+                .leak(),
+            stress: file.names.stress,
+        };
+        let mappings = file
+            .mappings
+            .into_iter()
+            .map(|mapping| Mapping {
+                from: leak(mapping.from),
+                to: leak(mapping.to),
+            })
+            .collect();
+
+        let transcriber = Self::from_rules(
+            leak(file.lang_name),
+            leak(file.lang_code),
+            rules,
+            post_rules,
+            exceptions,
+            markers,
+            names,
+            mappings,
+        )?;
+        if file.stages.is_empty() {
+            return Ok(transcriber);
+        }
+        transcriber.with_stages(file.stages.leak())
+    }
+
+    /// Replaces pipeline stages (`pipeline::DEFAULT` by default), input
+    /// stages first
+    pub fn with_stages(self, stages: &'static [Stage]) -> Result<Self, Error> {
+        if !pipeline::in_order(stages) {
+            return Err(Error::StageOrder);
+        }
+        Ok(Self { stages, ..self })
+    }
+
+    /// Reads rule file from disk
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Returns mappings the transcriber fails to reproduce
+    pub fn check_mappings(&self) -> Vec<(Mapping<'static>, String)> {
+        self.mappings
+            .iter()
+            .map(|mapping| (*mapping, self.transcribe(mapping.from)))
+            .filter(|(mapping, result)| result != mapping.to)
+            .collect()
+    }
+}
+
+fn push_rules(
+    rules: &mut Vec<Rule>,
+    section: &'static str,
+    table: IndexMap<String, RuleFileValue>,
+) -> Result<(), Error> {
+    for (key, value) in table {
+        let mut readings = match value {
+            RuleFileValue::One(value) => vec![leak(value)],
+            RuleFileValue::Many(values) => {
+                values.into_iter().map(leak).collect()
+            }
+            RuleFileValue::Section(table) => {
+                push_rules(rules, leak(key), table)?;
+                continue;
+            }
+        };
+        if readings.is_empty() {
+            return Err(Error::EmptyValue);
+        }
+        let value = readings.remove(0);
+        rules.push(Rule {
+            key: key_of(&key),
+            value,
+            alternatives: readings.leak(),
+            section,
+        });
+    }
+    Ok(())
+}
+
+// Keys match uppercased input, so they are normalized and uppercased too
+fn key_of(source: &str) -> &'static str {
+    leak(normalize::normalize(source).text.to_uppercase())
+}
+
+fn leak(string: String) -> &'static str {
+    string.leak()
+}
+
+impl TranscriberTrait for Transcriber {
+    fn get_lang_name(&self) -> &'static str {
+        self.lang_name
+    }
+
+    fn get_lang_code(&self) -> &'static str {
+        self.lang_code
+    }
+
+    fn get_rules(&self) -> &'static [Rule] {
+        self.trie.rules()
+    }
+
+    fn get_trie(&self) -> Trie<'_> {
+        self.trie.trie()
+    }
+
+    fn get_post_rules(&self) -> &'static [Rule] {
+        self.post_trie.rules()
+    }
+
+    fn get_post_trie(&self) -> Trie<'_> {
+        self.post_trie.trie()
+    }
+
+    fn get_exceptions(&self) -> &'static [Entry] {
+        self.exceptions
+    }
+
+    fn get_markers(&self) -> &'static [&'static str] {
+        self.markers
+    }
+
+    fn get_name_convention(&self) -> Convention {
+        self.names
+    }
+
+    fn get_stages(&self) -> &'static [Stage] {
+        self.stages
+    }
+
+    fn get_mappings(&self) -> Vec<Mapping<'_>> {
+        self.mappings.clone()
+    }
+}
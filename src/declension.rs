@@ -0,0 +1,151 @@
+//! Russian declension of transcribed names.
+//!
+//! Borrowed names follow the rules of Russian grammar: male names ending in
+//! a consonant, `й` or `ь` take noun endings (Нгуен → Нгуену, Линь → Линю),
+//! female names ending in `ь` follow the third declension (Линь → Лини),
+//! vowel-final names and other female names are indeclinable. Only the last
+//! part of a hyphenated name declines (Чан-Оча).
+
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::name::{Gender, Name, Role};
+
+/// Grammatical case
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum Case {
+    Nominative,
+    Genitive,
+    Dative,
+    Accusative,
+    Instrumental,
+    Prepositional,
+}
+
+impl Case {
+    /// Russian name of the case
+    pub fn name(self) -> &'static str {
+        match self {
+            Case::Nominative => "Именительный",
+            Case::Genitive => "Родительный",
+            Case::Dative => "Дательный",
+            Case::Accusative => "Винительный",
+            Case::Instrumental => "Творительный",
+            Case::Prepositional => "Предложный",
+        }
+    }
+}
+
+/// Declines every word of transcribed text
+pub fn decline(text: &str, gender: Gender, case: Case) -> String {
+    let words: Vec<_> =
+        text.split(' ').map(|word| decline_word(word, gender, case)).collect();
+    words.join(" ")
+}
+
+/// Declines parsed name (middle names and honorifics stay as they are)
+pub fn decline_name(name: &Name, gender: Gender, case: Case) -> Name {
+    let mut name = name.clone();
+    for part in &mut name.parts {
+        if matches!(part.role, Role::Family | Role::Given) {
+            part.text = decline_word(&part.text, gender, case);
+        }
+    }
+    name
+}
+
+/// Declines edited transcription of parsed name: words take the roles of
+/// the parts they line up with, or else the first and the last name parts
+/// stay at the ends with middle names between them
+pub fn decline_edited(
+    name: &Name,
+    text: &str,
+    gender: Gender,
+    case: Case,
+) -> String {
+    let words: Vec<_> = text.split_whitespace().collect();
+    let roles: Vec<_> = if words.len() == name.parts.len() {
+        name.parts.iter().map(|part| part.role).collect()
+    } else {
+        let honorifics = name
+            .parts
+            .iter()
+            .take_while(|part| part.role == Role::Honorific)
+            .count();
+        let first = name.parts.get(honorifics).map(|part| part.role);
+        let last = name.parts.last().map(|part| part.role);
+        (0..words.len())
+            .map(|i| match i {
+                _ if i < honorifics => Some(Role::Honorific),
+                _ if i == words.len() - 1 => last,
+                _ if i == honorifics => first,
+                _ => Some(Role::Middle),
+            })
+            .map(|role| role.unwrap_or(Role::Middle))
+            .collect()
+    };
+
+    let words: Vec<_> = words
+        .into_iter()
+        .zip(roles)
+        .map(|(word, role)| match role {
+            Role::Family | Role::Given => decline_word(word, gender, case),
+            _ => word.to_string(),
+        })
+        .collect();
+    words.join(" ")
+}
+
+/// All six cases of transcribed text, nominative first
+pub fn paradigm(text: &str, gender: Gender) -> Vec<(Case, String)> {
+    Case::iter().map(|case| (case, decline(text, gender, case))).collect()
+}
+
+// Declines the last part of a (hyphenated) word
+fn decline_word(word: &str, gender: Gender, case: Case) -> String {
+    if let Some((head, last)) = word.rsplit_once('-') {
+        return format!("{head}-{}", decline_word(last, gender, case));
+    }
+
+    let Some(last) = word.chars().last() else {
+        return word.to_string();
+    };
+    let stem = &word[..word.len() - last.len_utf8()];
+    let (stem, endings) = match (gender, last.to_lowercase().next()) {
+        (Gender::Male, Some('й')) if stem.to_lowercase().ends_with('и') => {
+            (stem, ["я", "ю", "я", "ем", "и"])
+        }
+        (Gender::Male, Some('й' | 'ь')) => {
+            (stem, ["я", "ю", "я", "ем", "е"])
+        }
+        (Gender::Male, Some('ж' | 'ш' | 'ч' | 'щ' | 'ц')) => {
+            (word, ["а", "у", "а", "ем", "е"])
+        }
+        (Gender::Male, Some(c)) if is_consonant(c) => {
+            (word, ["а", "у", "а", "ом", "е"])
+        }
+        (Gender::Female, Some('ь')) => (stem, ["и", "и", "ь", "ью", "и"]),
+        _ => return word.to_string(),
+    };
+
+    let ending = match case {
+        Case::Nominative => return word.to_string(),
+        Case::Genitive => endings[0],
+        Case::Dative => endings[1],
+        Case::Accusative => endings[2],
+        Case::Instrumental => endings[3],
+        Case::Prepositional => endings[4],
+    };
+
+    // Endings of all-caps words are capitals too
+    if word.chars().count() > 1 && !word.chars().any(char::is_lowercase) {
+        stem.to_string() + &ending.to_uppercase()
+    } else {
+        stem.to_string() + ending
+    }
+}
+
+// Russian consonant letter (signs and `й` excluded)
+fn is_consonant(c: char) -> bool {
+    "бвгджзклмнпрстфхцчшщ".contains(c)
+}
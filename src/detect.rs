@@ -0,0 +1,118 @@
+//! Source language detection.
+//!
+//! Every transcriber scores the input: letter sequences typical of its
+//! language (markers) and its exceptions add to the score, graphemes its
+//! rules cannot transcribe (e.g. Vietnamese tone letters for Thai) subtract.
+//! Only languages with markers, exceptions or letters beyond basic Latin
+//! their rules transcribe are given confidence.
+
+use crate::normalize;
+use crate::trace::Origin;
+use crate::transcriber::{self, TranscriberEnum, TranscriberTrait};
+
+// Grapheme the rules of the language cannot transcribe
+const UNMATCHED_PENALTY: i32 = 3;
+
+/// Language guess (confidence is the share of the positive scores, none
+/// without anything typical of the language)
+#[derive(Clone, Copy)]
+pub struct Detection<'a> {
+    pub transcriber: &'a TranscriberEnum,
+    pub score: i32,
+    pub confidence: f32,
+}
+
+/// Ranks transcribers by their score on input, the most likely first
+pub fn detect<'a>(
+    transcribers: impl IntoIterator<Item = &'a TranscriberEnum>,
+    input: &str,
+) -> Vec<Detection<'a>> {
+    // Scores of languages with evidence, clamped at 0
+    let mut detections: Vec<_> = transcribers
+        .into_iter()
+        .map(|transcriber| {
+            let (evidence, penalty) = weigh(transcriber, input);
+            let positive = if evidence > 0 { evidence - penalty } else { 0 };
+            let detection = Detection {
+                transcriber,
+                score: evidence - penalty,
+                confidence: 0.0,
+            };
+            (detection, positive.max(0))
+        })
+        .collect();
+    detections.sort_by_key(|(d, _)| std::cmp::Reverse(d.score));
+
+    let total: i32 = detections.iter().map(|(_, positive)| positive).sum();
+    if total > 0 {
+        for (detection, positive) in &mut detections {
+            detection.confidence = *positive as f32 / total as f32;
+        }
+    }
+    detections.into_iter().map(|(detection, _)| detection).collect()
+}
+
+/// Scores how typical input is for the language of transcriber
+pub fn score<T: TranscriberTrait + ?Sized>(
+    transcriber: &T,
+    input: &str,
+) -> i32 {
+    let (evidence, penalty) = weigh(transcriber, input);
+    evidence - penalty
+}
+
+// Marker and exception hits, and penalty for untranscribable graphemes
+fn weigh<T: TranscriberTrait + ?Sized>(
+    transcriber: &T,
+    input: &str,
+) -> (i32, i32) {
+    let trace = transcriber.trace(input);
+    let mut evidence = 0;
+    let mut penalty = 0;
+
+    for segment in &trace.segments {
+        match segment.origin {
+            // Exceptions are names of the language by definition
+            Origin::Exception(entry) => {
+                evidence += entry.from.chars().count() as i32;
+            }
+            // So are letters beyond basic Latin its rules transcribe
+            Origin::Rule(rule) if !rule.pattern().0.is_ascii() => {
+                evidence += 1;
+            }
+            Origin::Unmatched => penalty += UNMATCHED_PENALTY,
+            _ => {}
+        }
+    }
+
+    let input = normalize::normalize(input).text.to_uppercase();
+    for (_, word) in transcriber::segments(&input) {
+        for marker in transcriber.get_markers() {
+            if has_marker(word, marker) {
+                evidence += marker.trim_matches('|').chars().count() as i32;
+            }
+        }
+    }
+
+    (evidence, penalty)
+}
+
+// Checks if word contains marker (`|` anchors it to the word start or end)
+fn has_marker(word: &str, marker: &str) -> bool {
+    let (body, start) = match marker.strip_prefix('|') {
+        Some(body) => (body, true),
+        None => (marker, false),
+    };
+    let (body, end) = match body.strip_suffix('|') {
+        Some(body) => (body, true),
+        None => (body, false),
+    };
+
+    match (start, end) {
+        _ if body.is_empty() => false,
+        (true, true) => word == body,
+        (true, false) => word.starts_with(body),
+        (false, true) => word.ends_with(body),
+        (false, false) => word.contains(body),
+    }
+}
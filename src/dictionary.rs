@@ -0,0 +1,62 @@
+//! Whole-name dictionaries consulted before the rule engine.
+//!
+//! Entries match whole words or multi-word names at a word start,
+//! case-insensitively but respecting diacritics (tones distinguish names).
+
+use unicode_normalization::char::is_combining_mark;
+
+/// Whole-name entry (from -> to)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entry {
+    pub from: &'static str,
+    pub to: &'static str,
+}
+
+/// Byte length of phrase at the text start, if it ends on a word boundary
+pub fn match_phrase(text: &str, phrase: &str) -> Option<usize> {
+    if phrase.is_empty() {
+        return None;
+    }
+
+    let mut chars = text.char_indices();
+    for expected in phrase.chars() {
+        let (_, c) = chars.next()?;
+        if c != expected && !c.to_uppercase().eq(expected.to_uppercase()) {
+            return None;
+        }
+    }
+
+    // Whole words only: the next character must not continue the word
+    // (combining marks continue the previous letter)
+    match chars.next() {
+        Some((len, c)) if !c.is_alphabetic() && !is_combining_mark(c) => {
+            Some(len)
+        }
+        Some(_) => None,
+        None => Some(text.len()),
+    }
+}
+
+/// Longest entry whose selected side starts the text
+pub fn find(
+    entries: &'static [Entry],
+    text: &str,
+    side: fn(&Entry) -> &'static str,
+) -> Option<(usize, &'static Entry)> {
+    entries
+        .iter()
+        .filter_map(|entry| Some((match_phrase(text, side(entry))?, entry)))
+        .max_by_key(|(len, _)| *len)
+}
+
+/// Adapts stored value to the case of the matched text: all caps and all
+/// lowercase input are followed, anything else keeps the stored spelling
+pub fn recase(matched: &str, value: &str) -> String {
+    if !matched.chars().any(char::is_lowercase) {
+        value.to_uppercase()
+    } else if !matched.chars().any(char::is_uppercase) {
+        value.to_lowercase()
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,495 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use iced::widget::{
+    button, checkbox, column, container, pick_list, row, text, text_input,
+};
+use iced::{Alignment, Element, Length, Task as Command};
+
+use cyrillify::declension::{self, Case};
+use cyrillify::name::{self, Gender, Name};
+use cyrillify::orthography::{AfterVowel, Orthography};
+use cyrillify::{Overrides, Registry, TranscriberTrait, stress, variants};
+use strum::IntoEnumIterator;
+
+use crate::config;
+
+/// Starts the application window
+pub fn run() -> ExitCode {
+    let result = iced::application(
+        "Cyrillify v0.4.1",
+        Cyrillify::update,
+        Cyrillify::view,
+    )
+    .window_size((400.0, 930.0))
+    .run_with(Cyrillify::new);
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("cyrillify: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+struct Cyrillify {
+    registry: Registry,
+    selected_language: &'static str,
+    // Language in use (picked or detected) and detection confidence
+    language: Option<&'static str>,
+    detection: Option<f32>,
+    reverse: bool,
+    stressed: bool,
+    orthography: Orthography,
+    // Forbidden sequences as typed (`ЬЁ=Ё, ЙЙ=Й`)
+    replacements: String,
+    input_text: String,
+    output_text: String,
+    alternatives: Vec<String>,
+    overrides: Overrides,
+    overrides_path: Option<PathBuf>,
+    overridden: bool,
+    // Parts of the input name and the gender they are declined in (with
+    // the parts that told it)
+    name: Option<Name>,
+    gender: &'static str,
+    gender_evidence: String,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    LanguageChanged(&'static str),
+    ReverseToggled(bool),
+    StressToggled(bool),
+    YoFolded(bool),
+    SoftSignDropped(bool),
+    AfterVowelPicked(&'static str),
+    ReplacementsChanged(String),
+    InputChanged(String),
+    OutputEdited(String),
+    AlternativePicked(String),
+    OverrideSaved,
+    OverrideRemoved,
+    GenderChanged(&'static str),
+}
+
+// Readings (or source spellings in reverse mode) offered in the dropdown
+const VARIANT_LIMIT: usize = 5;
+
+// Language choice detecting the language from input
+const AUTO: &str = "Автоопределение";
+
+// Spellings of Й/И after vowels
+const AFTER_VOWEL: [&str; 3] =
+    ["Й/И как в правилах", "Й после гласных", "И после гласных"];
+
+const MALE: &str = "Мужской";
+const FEMALE: &str = "Женский";
+
+impl Cyrillify {
+    fn new() -> (Self, Command<Message>) {
+        let registry = config::load_registry();
+        let overrides = config::load_overrides();
+        let overrides_path = config::overrides_path();
+
+        let cyrillify = Self {
+            registry,
+            selected_language: AUTO,
+            language: None,
+            detection: None,
+            reverse: false,
+            stressed: false,
+            orthography: Orthography::default(),
+            replacements: String::new(),
+            input_text: String::new(),
+            output_text: String::new(),
+            alternatives: Vec::new(),
+            overrides,
+            overrides_path,
+            overridden: false,
+            name: None,
+            gender: MALE,
+            gender_evidence: String::new(),
+        };
+        (cyrillify, Command::none())
+    }
+
+    // Picks language or detects it from input (source spellings only)
+    fn resolve_language(&mut self) {
+        self.detection = None;
+        self.language = if self.selected_language != AUTO {
+            Some(self.selected_language)
+        } else if self.reverse {
+            None
+        } else {
+            let detections = self.registry.detect(&self.input_text);
+            detections
+                .first()
+                .filter(|detection| detection.confidence > 0.0)
+                .map(|detection| {
+                    self.detection = Some(detection.confidence);
+                    detection.transcriber.get_lang_name()
+                })
+        };
+    }
+
+    fn retranscribe(&mut self) {
+        self.resolve_language();
+
+        // Nothing to do without a language
+        let Some(transcriber) =
+            self.language.and_then(|language| self.registry.find(language))
+        else {
+            self.output_text.clear();
+            self.alternatives.clear();
+            self.overridden = false;
+            self.name = None;
+            self.gender_evidence.clear();
+            return;
+        };
+
+        // The top reading goes to output, the rest to the dropdown
+        self.overridden = false;
+        self.name = (!self.reverse).then(|| {
+            name::parse(
+                transcriber,
+                &self.input_text,
+                &self.overrides,
+                &self.orthography,
+            )
+        });
+
+        // Gender told by the name, the picked one stays otherwise
+        self.gender_evidence.clear();
+        let inference = name::infer_gender(transcriber, &self.input_text);
+        if let (false, Some(gender)) = (self.reverse, inference.gender) {
+            self.gender = match gender {
+                Gender::Male => MALE,
+                Gender::Female => FEMALE,
+            };
+            let parts: Vec<_> =
+                inference.evidence.into_iter().map(|(part, _)| part).collect();
+            self.gender_evidence = format!("По имени: {}", parts.join(", "));
+        }
+        let mut readings: Vec<String> = if self.reverse {
+            transcriber
+                .reverse(&self.input_text, VARIANT_LIMIT)
+                .into_iter()
+                .map(|candidate| candidate.text)
+                .collect()
+        } else {
+            let trace = transcriber.trace_in(
+                &self.input_text,
+                &self.overrides,
+                &self.orthography,
+            );
+            self.overridden = trace.overridden();
+            // Stress marks, except on spellings set by the user
+            let stress = if self.stressed && !self.overridden {
+                transcriber.get_name_convention().stress
+            } else {
+                stress::Stress::None
+            };
+            variants::variants(
+                transcriber,
+                &self.input_text,
+                &self.overrides,
+                &self.orthography,
+                VARIANT_LIMIT,
+            )
+            .into_iter()
+            .map(|variant| stress::stress_text(&variant.text, stress))
+            .collect()
+        };
+
+        self.output_text = if readings.is_empty() {
+            String::new()
+        } else {
+            readings.remove(0)
+        };
+        self.alternatives = readings;
+    }
+
+    fn lang_code(&self) -> Option<&'static str> {
+        let transcriber = self.registry.find(self.language?)?;
+        Some(transcriber.get_lang_code())
+    }
+
+    // Output as overrides keep it (stress marks are shown, not spelled)
+    fn spelling(&self) -> String {
+        self.output_text.replace(stress::MARK, "")
+    }
+
+    fn save_overrides(&self) {
+        let Some(path) = &self.overrides_path else {
+            return;
+        };
+        if let Err(err) = self.overrides.save(path) {
+            eprintln!("{}: {}", path.display(), err);
+        }
+    }
+}
+
+impl Cyrillify {
+    fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::LanguageChanged(language) => {
+                self.selected_language = language;
+                self.retranscribe();
+            }
+            Message::ReverseToggled(reverse) => {
+                self.reverse = reverse;
+                self.retranscribe();
+            }
+            Message::StressToggled(stressed) => {
+                self.stressed = stressed;
+                self.retranscribe();
+            }
+            Message::YoFolded(fold_yo) => {
+                self.orthography.fold_yo = fold_yo;
+                self.retranscribe();
+            }
+            Message::SoftSignDropped(drop) => {
+                self.orthography.drop_final_soft_sign = drop;
+                self.retranscribe();
+            }
+            Message::AfterVowelPicked(after_vowel) => {
+                self.orthography.after_vowel = match after_vowel {
+                    a if a == AFTER_VOWEL[1] => AfterVowel::Short,
+                    a if a == AFTER_VOWEL[2] => AfterVowel::Full,
+                    _ => AfterVowel::Keep,
+                };
+                self.retranscribe();
+            }
+            Message::ReplacementsChanged(replacements) => {
+                self.orthography.replacements =
+                    parse_replacements(&replacements);
+                self.replacements = replacements;
+                self.retranscribe();
+            }
+            Message::InputChanged(new_text) => {
+                self.input_text = new_text;
+                self.retranscribe();
+            }
+            Message::OutputEdited(new_text) => {
+                self.output_text = new_text;
+            }
+            Message::AlternativePicked(alternative) => {
+                self.output_text = alternative;
+            }
+            Message::OverrideSaved => {
+                if let Some(lang_code) = self.lang_code() {
+                    self.overrides.insert(
+                        lang_code,
+                        &self.input_text,
+                        &self.spelling(),
+                    );
+                    self.save_overrides();
+                    self.retranscribe();
+                }
+            }
+            Message::OverrideRemoved => {
+                if let Some(lang_code) = self.lang_code() {
+                    self.overrides.remove(lang_code, &self.input_text);
+                    self.save_overrides();
+                    self.retranscribe();
+                }
+            }
+            Message::GenderChanged(gender) => {
+                self.gender = gender;
+            }
+        }
+        Command::none()
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let languages: Vec<_> =
+            std::iter::once(AUTO).chain(self.registry.lang_names()).collect();
+        let language_picker = pick_list(
+            languages,
+            Some(self.selected_language),
+            Message::LanguageChanged,
+        );
+
+        // Detection result, until the user picks a language
+        let detection = match (self.selected_language, self.language) {
+            (AUTO, Some(language)) => format!(
+                "Определён: {language} ({:.0}%)",
+                self.detection.unwrap_or_default() * 100.0
+            ),
+            (AUTO, None) if !self.input_text.trim().is_empty() => {
+                "Язык не определён, выберите его".to_string()
+            }
+            _ => String::new(),
+        };
+
+        let reverse_toggle = checkbox("Из кириллицы", self.reverse)
+            .on_toggle(Message::ReverseToggled);
+
+        let stress_toggle = checkbox("Ударения", self.stressed)
+            .on_toggle_maybe(
+                (!self.reverse).then_some(Message::StressToggled),
+            );
+
+        let input_field = text_input("Введите имя", &self.input_text)
+            .on_input(Message::InputChanged);
+
+        let output_field = text_input("Транскрипция", &self.output_text)
+            .on_input(Message::OutputEdited);
+
+        let alternatives = pick_list(
+            self.alternatives.as_slice(),
+            None::<String>,
+            Message::AlternativePicked,
+        )
+        .placeholder(format!("Другие варианты: {}", self.alternatives.len()));
+
+        let content = column![
+            text("Язык:"),
+            language_picker,
+            text(detection),
+            row![reverse_toggle, stress_toggle].spacing(20),
+            text("Имя:"),
+            input_field,
+            text("Транскрипция:"),
+            output_field,
+            alternatives,
+            self.orthography_controls(),
+            self.override_controls(),
+            self.declensions(),
+        ]
+        .spacing(15)
+        .align_x(Alignment::Center)
+        .padding([0, 20]);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Alignment::Center)
+            .align_y(Alignment::Center)
+            .into()
+    }
+
+    // Spelling conventions applied to the readings
+    fn orthography_controls(&self) -> Element<'_, Message> {
+        let forward = !self.reverse;
+        let fold_yo = checkbox("Е вместо Ё", self.orthography.fold_yo)
+            .on_toggle_maybe(forward.then_some(Message::YoFolded));
+        let soft_sign =
+            checkbox("Без Ь на конце", self.orthography.drop_final_soft_sign)
+                .on_toggle_maybe(forward.then_some(Message::SoftSignDropped));
+        let after_vowel = match self.orthography.after_vowel {
+            AfterVowel::Keep => AFTER_VOWEL[0],
+            AfterVowel::Short => AFTER_VOWEL[1],
+            AfterVowel::Full => AFTER_VOWEL[2],
+        };
+        // Reverse mode spells nothing, so the choice only shows
+        let after_vowel_picker: Element<_> = if forward {
+            pick_list(
+                AFTER_VOWEL,
+                Some(after_vowel),
+                Message::AfterVowelPicked,
+            )
+            .into()
+        } else {
+            text(after_vowel).into()
+        };
+
+        let replacements =
+            text_input("Замены: ЬЁ=Ё, ЙЙ=Й", &self.replacements)
+                .on_input_maybe(
+                    forward.then_some(Message::ReplacementsChanged),
+                );
+
+        column![
+            row![fold_yo, soft_sign].spacing(20),
+            after_vowel_picker,
+            replacements,
+        ]
+        .spacing(10)
+        .align_x(Alignment::Center)
+        .into()
+    }
+
+    // Edited output can be remembered as the spelling of the input
+    fn override_controls(&self) -> Element<'_, Message> {
+        let saved = self.lang_code().and_then(|lang_code| {
+            self.overrides.get(lang_code, &self.input_text)
+        });
+
+        let can_save = !self.reverse
+            && !self.input_text.trim().is_empty()
+            && !self.output_text.trim().is_empty()
+            && saved != Some(self.spelling().trim());
+        let save = button("Запомнить")
+            .on_press_maybe(can_save.then_some(Message::OverrideSaved));
+        let remove = button("Забыть").on_press_maybe(
+            (!self.reverse && saved.is_some())
+                .then_some(Message::OverrideRemoved),
+        );
+
+        let status = if self.overridden {
+            "Пользовательское написание"
+        } else {
+            ""
+        };
+
+        column![row![save, remove].spacing(10), text(status)]
+            .spacing(5)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    // Output in the other five cases, in the gender picked
+    fn declensions(&self) -> Element<'_, Message> {
+        if self.reverse {
+            return column![].into();
+        }
+        let gender_picker = pick_list(
+            [MALE, FEMALE],
+            Some(self.gender),
+            Message::GenderChanged,
+        );
+        let mut content = column![
+            row![text("Род:"), gender_picker]
+                .spacing(10)
+                .align_y(Alignment::Center),
+            text(&self.gender_evidence),
+        ]
+        .spacing(5)
+        .align_x(Alignment::Center);
+
+        let Some(name) = &self.name else {
+            return content.into();
+        };
+        if self.output_text.trim().is_empty() {
+            return content.into();
+        }
+
+        let gender = match self.gender {
+            FEMALE => Gender::Female,
+            _ => Gender::Male,
+        };
+        // Edited output declines in the roles of the parsed parts
+        for case in Case::iter().skip(1) {
+            let form = declension::decline_edited(
+                name,
+                &self.output_text,
+                gender,
+                case,
+            );
+            content = content.push(text(format!("{}: {form}", case.name())));
+        }
+        content.into()
+    }
+}
+
+// Forbidden sequences and replacements typed as `ЬЁ=Ё, ЙЙ=Й`
+fn parse_replacements(text: &str) -> Vec<(String, String)> {
+    text.split([',', ' '])
+        .filter_map(|pair| pair.split_once('='))
+        .filter(|(from, _)| !from.is_empty())
+        .map(|(from, to)| (from.to_string(), to.to_string()))
+        .collect()
+}
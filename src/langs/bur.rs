@@ -1,108 +1,122 @@
-#[cfg(test)]
-use crate::tests::Mapping;
+use crate::transcriber::Mapping;
 
 crate::create_transcriber!(
     lang_name: "Бирманский",
+    lang_code: "bur",
     lang_rules: {
         // All names are from wikipedia.org/wiki/name_name
-        // ===================================================================
-        //  LETTERS
-        // ===================================================================
-        "A" => "А", "B" => "Б", "C" => "К", "D" => "Д", "E" => "Е",
-        "F" => "Ф", "G" => "Г", "H" => "Х", "I" => "И", "J" => "ДЖ",
-        "K" => "К", "L" => "Л", "M" => "М", "N" => "Н", "O" => "О",
-        "P" => "П", "Q" => "К", "R" => "Р", "S" => "С", "T" => "Т",
-        "U" => "У", "V" => "В", "W" => "В", "X" => "КС", "Y" => "Й",
-        "Z" => "З",
+        "LETTERS" {
+            "A" => "А", "B" => "Б", "C" => "К", "D" => "Д", "E" => "Е",
+            "F" => "Ф", "G" => "Г", "H" => "Х", "I" => "И", "J" => "ДЖ",
+            "K" => "К", "L" => "Л", "M" => "М", "N" => "Н", "O" => "О",
+            "P" => "П", "Q" => "К", "R" => "Р", "S" => "С", "T" => "Т",
+            "U" => "У", "V" => "В", "W" => "В", "X" => "КС", "Y" => "Й",
+            "Z" => "З",
+        },
 
-        // ===================================================================
-        //  VOWELS & DIPHTONGS
-        // ===================================================================
-        // Voiced vowels
-        "AE" => "Э",     // Borrowed words
-        "AI" => "АЙ",    // Min Aung Hlaing -> Мин Аун Хлайн (SAC chairman)
-        "AW" => "О",     // Htin Kyaw -> Тхин Чжо (president)
-                         // Saw Maung -> Со Маун (SPDC chairman)
-        "AY" => "ЕЙ",    // Nay Pyi Taw -> Нейпидо (capital)
-        "AYE" => "Э",    // Maung Aye -> Маун Э (SPDC vice chairman)
-                         //       Aye Ko -> Е Ко (vice president) <imprecize>
-                         // "Э" suits better to avoid confusion with "YE" (Е)
-        "EE" => "И",     // Mee-Bone-Pyan U Kyaw Yin ->
-                         // Ми-Бон-Пьян У Чжо Йин
-        "EI" => "ЕЙ",    // Thein Sein -> Тейн Сейн (president)
-                         // Sein Lwin -> Сейн Лвин (president)
-        "OE" => "О",     // Soe Win -> Со Вин (prime minister)
-        "OO" => "У",     // Mya Tun Oo -> Мья Тун У (deputy prime minister)
-        "ON" => "ОУН",   // Monywa -> Моунъюа (capital town)
-        "YA" => "Я",     // Yangon -> Янгон (city)
-        "YE" => "Е",     // Ye, Myanmar -> Е, Мьянма (town)
-        "YU" => "Ю",     // San Yu -> Сан Ю (president)
-        "YW" => "Ю",     // Monywa -> Моунъюа (capital town)
-        // Unvoiced vowels
-        "ONE|" => "ОН",  // Mee-Bone-Pyan U Kyaw Yin -> Ми-Бон-Пьян У Чжо Йин
-        "OKE|" => "ОУ",  // Hoke -> Хоу
-        "INE|" => "АЙН", // Hain -> Хайн, -INE is less common that -AING
+        "VOWELS & DIPHTONGS" {
+            // Voiced vowels
+            "AE" => "Э",     // Borrowed words
+            "AI" => "АЙ",    // Min Aung Hlaing -> Мин Аун Хлайн (SAC chairman)
+            "AW" => "О",     // Htin Kyaw -> Тхин Чжо (president)
+                             // Saw Maung -> Со Маун (SPDC chairman)
+            "AY" => "ЕЙ",    // Nay Pyi Taw -> Нейпидо (capital)
+            "AYE" => "Э" | "Е", // Maung Aye -> Маун Э (SPDC vice chairman)
+                                //       Aye Ko -> Е Ко (vice president) <imprecize>
+                                // "Э" suits better to avoid confusion with "YE" (Е)
+            "EE" => "И",     // Mee-Bone-Pyan U Kyaw Yin ->
+                             // Ми-Бон-Пьян У Чжо Йин
+            "EI" => "ЕЙ",    // Thein Sein -> Тейн Сейн (president)
+                             // Sein Lwin -> Сейн Лвин (president)
+            "OE" => "О",     // Soe Win -> Со Вин (prime minister)
+            "OO" => "У",     // Mya Tun Oo -> Мья Тун У (deputy prime minister)
+            "ON" => "ОУН",   // Monywa -> Моунъюа (capital town)
+            "YA" => "Я",     // Yangon -> Янгон (city)
+            "YE" => "Е",     // Ye, Myanmar -> Е, Мьянма (town)
+            "YU" => "Ю",     // San Yu -> Сан Ю (president)
+            "YW" => "Ю",     // Monywa -> Моунъюа (capital town)
+            // Unvoiced vowels
+            "ONE|" => "ОН",  // Mee-Bone-Pyan U Kyaw Yin -> Ми-Бон-Пьян У Чжо Йин
+            "OKE|" => "ОУ",  // Hoke -> Хоу
+            "INE|" => "АЙН", // Hain -> Хайн, -INE is less common that -AING
+        },
 
-        // ===================================================================
-        //  CONSONANTS & ASPIRANTS
-        // ===================================================================
-        // SH sound
-        "SH" => "Ш",    // Daw Kin Win Shwe -> До Кин Вин Шве
-        "SW" => "ШВ",   // Myint Swe -> Мьин Шве (vice president)
-        // Aspiration consonants
-        "HK" => "КХ",   // Hkun Law -> Кхун Ло (king of Martaban)
-        "HP" => "ПХ",   // Hpa-an -> Пхаан (capital town)
-        "HT" => "ТХ",   // Htin Kyaw -> Тхин Чжо (president)
-        // Unvoiced consonants
-        "AR" => "А",    // Myanmar -> Мьянма (country)
-        "AUK" => "АУ",  // Sai Mauk Kham -> Сайн Мау Кхан (vice president)
-                        // Kyaukse -> Чжаусе (town)
-        "AT" => "А",    // Kyat -> Чжа (currency) [but "Кьят" is standard
-                        // only to name the currency correctly]
-        "IT" => "И",    // Myitkyina -> Мьичина (capital town)
-                        // Sittaung -> Ситаун (river)
-        "|TH" => "Т",   // Than Shwe -> Тан Шве (prime minister)
-        "INT|" => "ИН", // Myint Swe -> Мьин Шве (vice president)
-        "NG|" => "Н",   // Thakin Kodaw Hmaing -> Такин Кодо Хмайн (poet)
-        "NT|" => "Н",   // U Thant -> У Тан (UN secretary-general)
-        "AIK|" => "АЙ", // Sao Shwe Thaik -> Сао Шве Тай (president)
-        // Palatalized consontants
-        "CH" => "Ч",    // Borrowed words
-        "GY" => "ДЖ",   // Myo Gyi -> Мьо Джи (musician)
-        "KY" => "Ч",    // Myitkyina -> Мьичина (capital town)
-        "MY" => "МЬ",   // Myanmar -> Мьянма (country)
-        "NY" => "НЬ",   // Paganyaw -> Паганья (ethnic group)
-        "PY" => "ПЬ",   // Pyi -> Пьи (city)
-        // Palatalized explosive consonant (position dependent)
-        "|KY" => "ЧЖ",  // Htin Kyaw -> Тхин Чжо (president)
-                        // Aung San Suu Kyi -> Аун Сан Су Чжи
-                        // Kyaukse -> Чжаусе (town)
+        "CONSONANTS & ASPIRANTS" {
+            // SH sound
+            "SH" => "Ш",    // Daw Kin Win Shwe -> До Кин Вин Шве
+            "SW" => "ШВ",   // Myint Swe -> Мьин Шве (vice president)
+            // Aspiration consonants
+            "HK" => "КХ",   // Hkun Law -> Кхун Ло (king of Martaban)
+            "HP" => "ПХ",   // Hpa-an -> Пхаан (capital town)
+            "HT" => "ТХ",   // Htin Kyaw -> Тхин Чжо (president)
+            // Unvoiced consonants
+            "AR" => "А",    // Myanmar -> Мьянма (country)
+            "AUK" => "АУ",  // Sai Mauk Kham -> Сайн Мау Кхан (vice president)
+                            // Kyaukse -> Чжаусе (town)
+            "AT" => "А",    // Kyat -> Чжа (currency) [but "Кьят" is standard
+                            // only to name the currency correctly]
+            "IT" => "И",    // Myitkyina -> Мьичина (capital town)
+                            // Sittaung -> Ситаун (river)
+            "|TH" => "Т",   // Than Shwe -> Тан Шве (prime minister)
+            "INT|" => "ИН", // Myint Swe -> Мьин Шве (vice president)
+            "NG|" => "Н",   // Thakin Kodaw Hmaing -> Такин Кодо Хмайн (poet)
+            "NT|" => "Н",   // U Thant -> У Тан (UN secretary-general)
+            "AIK|" => "АЙ", // Sao Shwe Thaik -> Сао Шве Тай (president)
+            // Palatalized consontants
+            "CH" => "Ч",    // Borrowed words
+            "GY" => "ДЖ",   // Myo Gyi -> Мьо Джи (musician)
+            "KY" => "Ч",    // Myitkyina -> Мьичина (capital town)
+            "MY" => "МЬ",   // Myanmar -> Мьянма (country)
+            "NY" => "НЬ",   // Paganyaw -> Паганья (ethnic group)
+            "PY" => "ПЬ",   // Pyi -> Пьи (city)
+            // Palatalized explosive consonant (position dependent)
+            "|KY" => "ЧЖ",  // Htin Kyaw -> Тхин Чжо (president)
+                            // Aung San Suu Kyi -> Аун Сан Су Чжи
+                            // Kyaukse -> Чжаусе (town)
+        },
 
-        // ===================================================================
-        //  PALATALIZED CONSONANTS * DIPHTONGS
-        // ===================================================================
-        // "KYA" would be "ЧЖА", generalizable with KY+A -> ЧЖ+А
-        "MYA" => "МЬЯ", // Myanmar -> Мьянма (country)
-        "NYA" => "НЬЯ", // Nyaung-U -> Ньяун-У (town)
-        "PYA" => "ПЬЯ", // Bala Pyan -> Бала Пьян (dancer)
+        "PALATALIZED CONSONANTS * DIPHTONGS" {
+            // "KYA" would be "ЧЖА", generalizable with KY+A -> ЧЖ+А
+            "MYA" => "МЬЯ", // Myanmar -> Мьянма (country)
+            "NYA" => "НЬЯ", // Nyaung-U -> Ньяун-У (town)
+            "PYA" => "ПЬЯ", // Bala Pyan -> Бала Пьян (dancer)
 
-        // YO/YU/YW patterns
-        "KYO" => "ЧЬО", "KYU" => "ЧЬЮ", "KYW" => "ЧЬЮ",
-        "MYO" => "МЬО", "MYU" => "МЬЮ", "MYW" => "МЬЮ",
-        "NYO" => "НЬО", "NYU" => "НЬЮ", "NYW" => "НЬЮ",
-        "PYO" => "ПЬО", "PYU" => "ПЬЮ", "PYW" => "ПЬЮ",
-        // + ON pattern
-        "ONYO" => "ОУНЬО", "ONYU" => "ОУНЬЮ", "ONYW" => "ОУНЬЮ",
+            // YO/YU/YW patterns
+            "KYO" => "ЧЬО", "KYU" => "ЧЬЮ", "KYW" => "ЧЬЮ",
+            "MYO" => "МЬО", "MYU" => "МЬЮ", "MYW" => "МЬЮ",
+            "NYO" => "НЬО", "NYU" => "НЬЮ", "NYW" => "НЬЮ",
+            "PYO" => "ПЬО", "PYU" => "ПЬЮ", "PYW" => "ПЬЮ",
+            // + ON pattern
+            "ONYO" => "ОУНЬО", "ONYU" => "ОУНЬЮ", "ONYW" => "ОУНЬЮ",
 
-        // YINE patterns
-        "KYINE|" => "ЧЬЯЙН", "PYINE|" => "ПЬЯЙН",
-        "MYINE|" => "МЬЯЙН", "NYINE|" => "НЬЯЙН",
-
-        // ===================================================================
-        //  DICTIONARY
-        // ===================================================================
-
-        "|SAI|" => "САЙН",   // Sai Mauk Kham -> Сайн Мау Кхан
+            // YINE patterns
+            "KYINE|" => "ЧЬЯЙН", "PYINE|" => "ПЬЯЙН",
+            "MYINE|" => "МЬЯЙН", "NYINE|" => "НЬЯЙН",
+        },
+    },
+    exceptions: {
+        "Sai" => "Сайн",   // Sai Mauk Kham -> Сайн Мау Кхам
+        "Ba Swe" => "Ба Све",
+        "Soe Htut" => "Со Хтут",
+        "Henry Van Thio" => "Генри Ван Тио",
+    },
+    markers: {
+        // Initial clusters
+        "|KY", "|HT", "|HL", "|HK", "|HM", "|HN", "|MY", "|NY",
+        // Frequent syllables
+        "AUNG", "MAUNG", "MYINT", "NYUNT", "THEIN", "SEIN", "KHIN",
+        "SHWE", "|THAN|", "|TUN|", "|SOE|", "|MIN|", "|WIN|", "|SAW|",
+        "|AYE|", "|U|", "OO|", "|HLA|", "|MYA|", "KYAW", "|SAN|", "|YU|",
+        "|BO|", "|YA|", "|LET|", "|SWE|", "AW|", "AIN|", "OKE|",
+    },
+    names: {
+        order: GivenOnly,
+        honorifics: { "U", "Daw", "Ko", "Thakin", "Sayadaw", "Bogyoke" },
+        genders: {
+            "U" => Male, "Ko" => Male, "Maung" => Male, "Thakin" => Male,
+            "Daw" => Female, "Ma" => Female,
+        },
+        stress: EverySyllable,
     },
     mappings: {
         vec![
@@ -132,7 +146,7 @@ crate::create_transcriber!(
             // HEADS OF GOVERNMENT OF MYANMAR
             // Prime Ministers
             Mapping { from: "U Nu", to: "У Ну" },
-            // Mapping { from: "Ba Swe", to: "Ба Све" }, // Exc.
+            Mapping { from: "Ba Swe", to: "Ба Све" }, // Exc.
             Mapping { from: "Ne Win", to: "Не Вин" }, // Multiple times
             Mapping { from: "Sein Win", to: "Сейн Вин" },
             Mapping { from: "Maung Maung Kha", to: "Маун Маун Кха" },
@@ -166,12 +180,12 @@ crate::create_transcriber!(
             Mapping { from: "Sai Mauk Kham", to: "Сайн Мау Кхам" },
             Mapping { from: "Nyan Tun", to: "Ньян Тун" },
             Mapping { from: "Myint Swe", to: "Мьин Шве" },
-            // Mapping { from: "Henry Van Thio", to: "Генри Ван Тио" }, // Exc.
+            Mapping { from: "Henry Van Thio", to: "Генри Ван Тио" }, // Exc.
             // Deputy Prime Ministers (since 2021, under SAC, concurrent)
             Mapping { from: "Soe Win", to: "Со Вин" }, // Another one
             Mapping { from: "Mya Tun Oo", to: "Мья Тун У" },
             Mapping { from: "Tin Aung San", to: "Тин Аун Сан" },
-            // Mapping { from: "Soe Htut", to: "Со Хтут" }, // Exc.
+            Mapping { from: "Soe Htut", to: "Со Хтут" }, // Exc.
             Mapping { from: "Win Shein", to: "Вин Шейн" },
             Mapping { from: "Than Swe", to: "Тан Шве" }, // Another one
             Mapping { from: "Nyo Saw", to: "Ньо Со"},
@@ -1,89 +1,91 @@
-#[cfg(test)]
-use crate::tests::Mapping;
+use crate::transcriber::Mapping;
 
 crate::create_transcriber!(
     lang_name: "Тайский",
+    lang_code: "tha",
     lang_rules: {
         // Royal Thai General System of Transcription (RTGS)
         // adapted for Russian Cyrillic
-        // ===================================================================
-        //  LETTERS
-        // ===================================================================
-        "A" => "А", "B" => "Б", "C" => "К", "D" => "Д",
-        "E" => "Е", "F" => "Ф", "G" => "Г", "H" => "Х",
-        "I" => "И", "J" => "Ч", "K" => "К", "L" => "Л",
-        "M" => "М", "N" => "Н", "O" => "О", "P" => "П",
-        "R" => "Р", "S" => "С", "T" => "Т", "U" => "У",
-        "V" => "В", "W" => "В", "X" => "КС", "Y" => "Й",
-        "Z" => "З",
+        "LETTERS" {
+            "A" => "А", "B" => "Б", "C" => "К", "D" => "Д",
+            "E" => "Е", "F" => "Ф", "G" => "Г", "H" => "Х",
+            "I" => "И", "J" => "Ч", "K" => "К", "L" => "Л",
+            "M" => "М", "N" => "Н", "O" => "О", "P" => "П",
+            "R" => "Р", "S" => "С", "T" => "Т", "U" => "У",
+            "V" => "В", "W" => "В", "X" => "КС", "Y" => "Й",
+            "Z" => "З",
+        },
 
-        // ===================================================================
-        //  CONSONANTS & ASPIRANTS
-        // ===================================================================
-        "K" => "К", "KH" => "КХ",
-        "P" => "П", "PH" => "ПХ", "BH" => "ПХ",
-        "T" => "Т", "TH" => "ТХ",
-        "CH" => "Ч", "SH" => "Ч",
-        "NG" => "НГ",
-        "JJ" => "ТЧ",
+        "CONSONANTS & ASPIRANTS" {
+            "KH" => "КХ",
+            "PH" => "ПХ", "BH" => "ПХ",
+            "TH" => "ТХ",
+            "CH" => "Ч", "SH" => "Ч",
+            "NG" => "НГ",
+            "JJ" => "ТЧ",
 
-        // P + Vowels & Diphthongs (deviations from RTGS)
-        "PA" => "ПХА", "PE" => "ПХЕ", "PI" => "ПХИ",
-        "PO" => "ПХО", "PU" => "ПХУ",
+            // P/T aspirated before vowels (deviations from RTGS)
+            "P / _[AEIOU]" => "ПХ",
+            "T / _[AEIOU]" => "ТХ",
+        },
 
-        "PAI" => "ПХАЙ", "PAE" => "ПХЭ",
-        "PEE" => "ПХИ", "PEO" => "ПХЭО", "PER" => "ПХЭ",
-        "PIA" => "ПХИА", "PIE" => "ПХИА",
-        "POE" => "ПХЕ", "POI" => "ПХОЙ",
-        "PUE" => "ПХЫ",
+        "VOWELS & DIPHTHONGS" {
+            "AI" => "АЙ", "AE" => "Э",
+            "EE" => "И", "EO" => "ЭО", "ER" => "Э",
+            "IA" => "ИА", "IE" => "ИА",
+            "OE" => "Е", "OI" => "ОЙ",
+            "UE" => "Ы",
 
-        // T + Vowels & Diphthongs (deviations from RTGS)
-        "TA" => "ТХА", "TE" => "ТХЕ", "TI" => "ТХИ",
-        "TO" => "ТХО", "TU" => "ТХУ",
+            // YA/YU
+            "YA" => "ЬЯ",
+            "YU" => "Ю",
 
-        "TAI" => "ТХАЙ", "TAE" => "ТХЭ",
-        "TEE" => "ТХИ", "TEO" => "ТХЭО", "TER" => "ТХЭ",
-        "TIA" => "ТХИА", "TIE" => "ТХИА",
-        "TOE" => "ТХЕ", "TOI" => "ТХОЙ",
-        "TUE" => "ТХЫ",
+            // YA diphtong variations
+            "AYA" => "АЯ",  "EYA" => "ЕЯ", "IYA" => "ИЯ",
+            "OYA" => "ОЯ", "UYA" => "УЯ",
+            // Aspirated P/T keep their vowel apart from YA
+            "AYA / [PT]_" => "АЬЯ", "EYA / [PT]_" => "ЕЬЯ",
+            "IYA / [PT]_" => "ИЬЯ", "OYA / [PT]_" => "ОЬЯ",
+            "UYA / [PT]_" => "УЬЯ",
+        },
 
-        // ===================================================================
-        //  VOWELS & DIPHTHONGS
-        // ===================================================================
-        "AI" => "АЙ", "AE" => "Э",
-        "EE" => "И", "EO" => "ЭО", "ER" => "Э",
-        "IA" => "ИА", "IE" => "ИА",
-        "OE" => "Е", "OI" => "ОЙ",
-        "UE" => "Ы",
+        "POSITION DEPENDENT" {
+            // Initial consonant clusters
+            "|SR" => "С",
 
-        // YA/YU
-        "YA" => "ЬЯ",
-        "YU" => "Ю",
-
-        // YA diphtong variations
-        "AYA" => "АЯ",  "EYA" => "ЕЯ", "IYA" => "ИЯ",
-        "OYA" => "ОЯ", "UYA" => "УЯ",
-
-        // ===================================================================
-        //  POSITION DEPENDENT
-        // ===================================================================
-        // Initial consonant clusters
-        "|SR" => "С",
-
-        // Final consonants
-        "CH|" => "Т", "TH|" => "Т", "S|" => "Т",
-        "KH|" => "К",
-        "J|" => "Т",
-        "L|" => "Н", "N|" => "Н", "R|" => "Н",
-        "RN|" => "Н",
-        "F|" => "П", "PH|" => "П",
-        "TRA|" => "Т",
-        "UCK|" => "АК",
-
-        // ===================================================================
-        //  DICTIONARY
-        // ===================================================================
-        "THAKSIN" => "ТАКСИН",
+            // Final consonants
+            "CH|" => "Т", "TH|" => "Т", "S|" => "Т",
+            "KH|" => "К",
+            "J|" => "Т",
+            "L|" => "Н", "N|" => "Н", "R|" => "Н",
+            "RN|" => "Н",
+            "F|" => "П", "PH|" => "П",
+            "TRA|" => "Т",
+            "UCK|" => "АК",
+        },
+    },
+    exceptions: {
+        "Thaksin" => "Таксин",
+        "Prayut Chan-o-cha" => "Прают Чан-Оча",
+    },
+    markers: {
+        // Frequent name endings
+        "SAWAT|", "WATRA|", "NONT|", "CHAI|", "PORN|", "SAK|", "RAT|",
+        "WAT|", "SIRI", "KIAT", "WONG", "PAI|", "PHON",
+        // Frequent name beginnings
+        "|SOM", "|SUR", "|CHUL", "|YING", "|SRI", "|PRA",
+        // Vowel spellings of RTGS
+        "AE", "UEA", "OE",
+    },
+    names: {
+        order: FamilyLast,
+        honorifics: { "Khun", "Nai", "Nang", "Nangsao", "Khunying", "Thanphuying" },
+        genders: {
+            "Nai" => Male,
+            "Nang" => Female, "Nangsao" => Female,
+            "Khunying" => Female, "Thanphuying" => Female,
+        },
+        stress: Final,
     },
     mappings: {
         vec![
@@ -97,7 +99,7 @@ crate::create_transcriber!(
             Mapping { from: "Somchai Wongsawat", to: "Сомчай Вонгсават" },
             Mapping { from: "Abhisit Vejjajiva", to: "Апхисит Ветчачива" },
             Mapping { from: "Yingluck Shinawatra", to: "Йинглак Чинават" },
-            // Mapping { from: "Prayut Chan-o-cha", to: "Прают Чан-Оча" },
+            Mapping { from: "Prayut Chan-o-cha", to: "Прают Чан-Оча" },
             Mapping { from: "Srettha Thavisin", to: "Сеттха Тхависин" },
             Mapping { from: "Paetongtarn Shinawatra", to: "Пхэтхонгтхан Чинават" },
         ]
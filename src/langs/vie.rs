@@ -1,114 +1,137 @@
-#[cfg(test)]
-use crate::tests::Mapping;
+use crate::transcriber::Mapping;
 
 crate::create_transcriber!(
     lang_name: "Вьетнамский",
+    lang_code: "vie",
     lang_rules: {
         // Based on the Serdyuchenko system for Vietnamese Cyrillization.
-        // ===================================================================
-        //  LETTERS (BASE & TONELESS DIACRITICS)
-        // ===================================================================
-        "A" => "А", "B" => "Б", "C" => "К", "D" => "З",
-        "E" => "Е", "F" => "Ф", "G" => "Г", "H" => "Х",
-        "I" => "И", "K" => "К", "L" => "Л", "M" => "М",
-        "N" => "Н", "O" => "О", "P" => "П", "Q" => "К",
-        "R" => "Р", "S" => "Ш", "T" => "Т", "U" => "У",
-        "V" => "В", "X" => "С", "Y" => "И", "Z" => "З",
-        "Đ" => "Д",
-        "Ă" => "А", "Â" => "А",
-        "Ê" => "Е", "Ô" => "О", "Ơ" => "О", "Ư" => "Ы",
+        "LETTERS (BASE & TONELESS DIACRITICS)" {
+            "A" => "А", "B" => "Б", "C" => "К", "D" => "З",
+            "E" => "Е", "F" => "Ф", "G" => "Г", "H" => "Х",
+            "I" => "И", "K" => "К", "L" => "Л", "M" => "М",
+            "N" => "Н", "O" => "О", "P" => "П", "Q" => "К",
+            "R" => "Р", "S" => "Ш", "T" => "Т", "U" => "У",
+            "V" => "В", "X" => "С", "Y" => "И", "Z" => "З",
+            "Đ" => "Д",
+            "Ă" => "А", "Â" => "А",
+            "Ê" => "Е", "Ô" => "О", "Ơ" => "О", "Ư" => "Ы",
+        },
 
-        // ===================================================================
-        //  COMPLEX VOWEL CLUSTERS WITH TONE MARKS
-        // ===================================================================
-        "UYÊN"=>"УЕН", "UYỀN"=>"УЕН", "UYẾN"=>"УЕН",
-        "UYỂN"=>"УЕН", "UYỄN"=>"УЕН", "UYỆN"=>"УЕН",
-        "UYÊ"=>"УЕН", "UYỀ"=>"УЕН", "UYẾ"=>"УЕН",
-        "UYỂ"=>"УЕН", "UYỄ"=>"УЕН", "UYỆ"=>"УЕН",
-        "IÊU"=>"ЬЕУ", "IỀU"=>"ЬЕУ", "IẾU"=>"ЬЕУ",
-        "IỂU"=>"ЬЕУ", "IỄU"=>"ЬЕУ", "IỆU"=>"ЬЕУ",
-        "YÊU"=>"ЙЕУ", "YỀU"=>"ЙЕУ", "YẾU"=>"ЙЕУ",
-        "YỂU"=>"ЙЕУ", "YỄU"=>"ЙЕУ", "YỆU"=>"ЙЕУ",
-        "ƯƠU"=>"ЫОУ", "ƯỜU"=>"ЫОУ", "ƯỚU"=>"ЫОУ",
-        "ƯỞU"=>"ЫОУ", "ƯỠU"=>"ЫОУ", "ƯỢU"=>"ЫОУ",
-        "ƯƠI"=>"ЫОЙ", "ƯỜI"=>"ЫОЙ", "ƯỚI"=>"ЫОЙ",
-        "ƯỞI"=>"ЫОЙ", "ƯỠI"=>"ЫОЙ", "ƯỢI"=>"ЫОЙ",
-        "IÊ"=>"ЬЕ", "IỀ"=>"ЬЕ", "IẾ"=>"ЬЕ", "IỂ"=>"ЬЕ", "IỄ"=>"ЬЕ", "IỆ"=>"ЬЕ",
-        "AI"=>"АЙ", "ÀI"=>"АЙ", "ÁI"=>"АЙ", "ẢI"=>"АЙ", "ÃI"=>"АЙ", "ẠI"=>"АЙ",
-        "AY"=>"АЙ", "ÀY"=>"АЙ", "ÁY"=>"АЙ", "ẢY"=>"АЙ", "ÃY"=>"АЙ", "ẠY"=>"АЙ",
-        "OI"=>"ОЙ", "ÒI"=>"ОЙ", "ÓI"=>"ОЙ", "ỎI"=>"ОЙ", "ÕI"=>"ОЙ", "ỌI"=>"ОЙ",
-        "ÔI"=>"ОЙ", "ỒI"=>"ОЙ", "ỐI"=>"ОЙ", "ỔI"=>"ОЙ", "ỖI"=>"ОЙ", "ỘI"=>"ОЙ",
-        "ƠI"=>"ОЙ", "ỜI"=>"ОЙ", "ỚI"=>"ОЙ", "ỞI"=>"ОЙ", "ỠI"=>"ОЙ", "ỢI"=>"ОЙ",
-        "UI"=>"УЙ", "ÙI"=>"УЙ", "ÚI"=>"УЙ", "ỦI"=>"УЙ", "ŨI"=>"УЙ", "ỤI"=>"УЙ",
-        "ƯI"=>"ЫЙ", "ỪI"=>"ЫЙ", "ỨI"=>"ЫЙ", "ỬI"=>"ЫЙ", "ỮI"=>"ЫЙ", "ỰI"=>"ЫЙ",
-        "ÂY"=>"ЭЙ", "ẦY"=>"ЭЙ", "ẤY"=>"ЭЙ", "ẨY"=>"ЭЙ", "ẪY"=>"ЭЙ", "ẬY"=>"ЭЙ",
+        "COMPLEX VOWEL CLUSTERS WITH TONE MARKS" {
+            "UYÊN"=>"УЕН", "UYỀN"=>"УЕН", "UYẾN"=>"УЕН",
+            "UYỂN"=>"УЕН", "UYỄN"=>"УЕН", "UYỆN"=>"УЕН",
+            "UYÊ"=>"УЕН", "UYỀ"=>"УЕН", "UYẾ"=>"УЕН",
+            "UYỂ"=>"УЕН", "UYỄ"=>"УЕН", "UYỆ"=>"УЕН",
+            "IÊU"=>"ЬЕУ", "IỀU"=>"ЬЕУ", "IẾU"=>"ЬЕУ",
+            "IỂU"=>"ЬЕУ", "IỄU"=>"ЬЕУ", "IỆU"=>"ЬЕУ",
+            "YÊU"=>"ЙЕУ", "YỀU"=>"ЙЕУ", "YẾU"=>"ЙЕУ",
+            "YỂU"=>"ЙЕУ", "YỄU"=>"ЙЕУ", "YỆU"=>"ЙЕУ",
+            "ƯƠU"=>"ЫОУ", "ƯỜU"=>"ЫОУ", "ƯỚU"=>"ЫОУ",
+            "ƯỞU"=>"ЫОУ", "ƯỠU"=>"ЫОУ", "ƯỢU"=>"ЫОУ",
+            "ƯƠI"=>"ЫОЙ", "ƯỜI"=>"ЫОЙ", "ƯỚI"=>"ЫОЙ",
+            "ƯỞI"=>"ЫОЙ", "ƯỠI"=>"ЫОЙ", "ƯỢI"=>"ЫОЙ",
+            "IÊ"=>"ЬЕ", "IỀ"=>"ЬЕ", "IẾ"=>"ЬЕ", "IỂ"=>"ЬЕ", "IỄ"=>"ЬЕ", "IỆ"=>"ЬЕ",
+            "AI"=>"АЙ", "ÀI"=>"АЙ", "ÁI"=>"АЙ", "ẢI"=>"АЙ", "ÃI"=>"АЙ", "ẠI"=>"АЙ",
+            "AY"=>"АЙ", "ÀY"=>"АЙ", "ÁY"=>"АЙ", "ẢY"=>"АЙ", "ÃY"=>"АЙ", "ẠY"=>"АЙ",
+            "OI"=>"ОЙ", "ÒI"=>"ОЙ", "ÓI"=>"ОЙ", "ỎI"=>"ОЙ", "ÕI"=>"ОЙ", "ỌI"=>"ОЙ",
+            "ÔI"=>"ОЙ", "ỒI"=>"ОЙ", "ỐI"=>"ОЙ", "ỔI"=>"ОЙ", "ỖI"=>"ОЙ", "ỘI"=>"ОЙ",
+            "ƠI"=>"ОЙ", "ỜI"=>"ОЙ", "ỚI"=>"ОЙ", "ỞI"=>"ОЙ", "ỠI"=>"ОЙ", "ỢI"=>"ОЙ",
+            "UI"=>"УЙ", "ÙI"=>"УЙ", "ÚI"=>"УЙ", "ỦI"=>"УЙ", "ŨI"=>"УЙ", "ỤI"=>"УЙ",
+            "ƯI"=>"ЫЙ", "ỪI"=>"ЫЙ", "ỨI"=>"ЫЙ", "ỬI"=>"ЫЙ", "ỮI"=>"ЫЙ", "ỰI"=>"ЫЙ",
+            "ÂY"=>"ЭЙ", "ẦY"=>"ЭЙ", "ẤY"=>"ЭЙ", "ẨY"=>"ЭЙ", "ẪY"=>"ЭЙ", "ẬY"=>"ЭЙ",
+        },
 
-        // ===================================================================
-        //  VOWELS WITH TONE MARKS
-        // ===================================================================
-        "À"=>"А", "Á"=>"А", "Ả"=>"А", "Ã"=>"А", "Ạ"=>"А",
-        "Ằ"=>"А", "Ắ"=>"А", "Ẳ"=>"А", "Ẵ"=>"А", "Ặ"=>"А",
-        "Ầ"=>"А", "Ấ"=>"А", "Ẩ"=>"А", "Ẫ"=>"А", "Ậ"=>"А",
-        "È"=>"Е", "É"=>"Е", "Ẻ"=>"Е", "Ẽ"=>"Е", "Ẹ"=>"Е",
-        "Ề"=>"Е", "Ế"=>"Е", "Ể"=>"Е", "Ễ"=>"Е", "Ệ"=>"Е",
-        "Ì"=>"И", "Í"=>"И", "Ỉ"=>"И", "Ĩ"=>"И", "Ị"=>"И",
-        "Ò"=>"О", "Ó"=>"О", "Ỏ"=>"О", "Õ"=>"О", "Ọ"=>"О",
-        "Ồ"=>"О", "Ố"=>"О", "Ổ"=>"О", "Ỗ"=>"О", "Ộ"=>"О",
-        "Ờ"=>"О", "Ớ"=>"О", "Ở"=>"О", "Ỡ"=>"О", "Ợ"=>"О",
-        "Ù"=>"У", "Ú"=>"У", "Ủ"=>"У", "Ũ"=>"У", "Ụ"=>"У",
-        "Ừ"=>"Ы", "Ứ"=>"Ы", "Ử"=>"Ы", "Ữ"=>"Ы", "Ự"=>"Ы",
-        "Ỳ"=>"И", "Ý"=>"И", "Ỷ"=>"И", "Ỹ"=>"И", "Ỵ"=>"И",
+        "VOWELS WITH TONE MARKS" {
+            "À"=>"А", "Á"=>"А", "Ả"=>"А", "Ã"=>"А", "Ạ"=>"А",
+            "Ằ"=>"А", "Ắ"=>"А", "Ẳ"=>"А", "Ẵ"=>"А", "Ặ"=>"А",
+            "Ầ"=>"А", "Ấ"=>"А", "Ẩ"=>"А", "Ẫ"=>"А", "Ậ"=>"А",
+            "È"=>"Е", "É"=>"Е", "Ẻ"=>"Е", "Ẽ"=>"Е", "Ẹ"=>"Е",
+            "Ề"=>"Е", "Ế"=>"Е", "Ể"=>"Е", "Ễ"=>"Е", "Ệ"=>"Е",
+            "Ì"=>"И", "Í"=>"И", "Ỉ"=>"И", "Ĩ"=>"И", "Ị"=>"И",
+            "Ò"=>"О", "Ó"=>"О", "Ỏ"=>"О", "Õ"=>"О", "Ọ"=>"О",
+            "Ồ"=>"О", "Ố"=>"О", "Ổ"=>"О", "Ỗ"=>"О", "Ộ"=>"О",
+            "Ờ"=>"О", "Ớ"=>"О", "Ở"=>"О", "Ỡ"=>"О", "Ợ"=>"О",
+            "Ù"=>"У", "Ú"=>"У", "Ủ"=>"У", "Ũ"=>"У", "Ụ"=>"У",
+            "Ừ"=>"Ы", "Ứ"=>"Ы", "Ử"=>"Ы", "Ữ"=>"Ы", "Ự"=>"Ы",
+            "Ỳ"=>"И", "Ý"=>"И", "Ỷ"=>"И", "Ỹ"=>"И", "Ỵ"=>"И",
+        },
 
-        // ===================================================================
-        //  CONSONANTS & DIGRAPHS
-        // ===================================================================
-        "PH" => "Ф",
-        "GH" => "Г", "NGH" => "НГ",
-        "GI" => "З",
-        "CH" => "Ч",
-        "TR" => "Ч",
-        "NH" => "НЬ",
-        "TH" => "ТХ",
-        "CH|" => "ТЬ", "NH|" => "НЬ",
+        "CONSONANTS & DIGRAPHS" {
+            "PH" => "Ф",
+            "GH" => "Г", "NGH" => "НГ",
+            "GI" => "З",
+            "CH" => "Ч",
+            "TR" => "Ч",
+            "NH" => "НЬ",
+            "TH" => "ТХ",
+            "CH|" => "ТЬ", "NH|" => "НЬ" | "Н",
+        },
 
-        // ===================================================================
-        //  VOWELS & DIPHTHONGS (Toneless)
-        // ===================================================================
-        "|E" => "Э",
-        "ÂI" => "ЭЙ",
-        "AU" => "АУ", "ÂU" => "ЭУ",
-        "IU" => "ИУ", "ƯU" => "ЫУ",
-        "EO" => "ЭО",
-        "UY" => "УИ",
-        "IA" => "ЬЯ", "YA" => "ЬЯ",
-        "YÊ" => "ЙЕ", "|YÊ" => "ЙЕ",
-        "IO" => "ЬЁ", "YO" => "ЬЁ",
-        "OA" => "ОА", "OAI" => "ОАЙ", "OAN" => "ОАН",
-        "OE" => "ОЕ", "OEO" => "ОЭО",
+        "VOWELS & DIPHTHONGS (Toneless)" {
+            "|E" => "Э",
+            "ÂI" => "ЭЙ",
+            "AU" => "АУ", "ÂU" => "ЭУ",
+            "IU" => "ИУ", "ƯU" => "ЫУ",
+            "EO" => "ЭО",
+            "UY" => "УИ",
+            "IA" => "ЬЯ", "YA" => "ЬЯ",
+            "YÊ" => "ЙЕ", "|YÊ" => "ЙЕ",
+            "IO" => "ЬЁ", "YO" => "ЬЁ",
+            "OA" => "ОА", "OAI" => "ОАЙ", "OAN" => "ОАН",
+            "OE" => "ОЕ", "OEO" => "ОЭО",
+        },
 
-        // ===================================================================
-        //  PALATALIZATION PATTERNS
-        // ===================================================================
-        "CHI"=>"ТИ", "CHÌ"=>"ТИ", "CHÍ"=>"ТИ",
-        "CHỈ"=>"ТИ", "CHĨ"=>"ТИ", "CHỊ"=>"ТИ",
-        "CHE"=>"ТЕ", "CHÈ"=>"ТЕ", "CHÉ"=>"ТЕ",
-        "CHẺ"=>"ТЕ", "CHẼ"=>"ТЕ", "CHẸ"=>"ТЕ",
-        "CHÊ"=>"ТЕ", "CHỀ"=>"ТЕ", "CHẾ"=>"ТЕ",
-        "CHỂ"=>"ТЕ", "CHỄ"=>"ТЕ", "CHỆ"=>"ТЕ",
-        "GIA"=>"ЗЯ", "GIÀ"=>"ЗЯ", "GIÁ"=>"ЗЯ",
-        "GIẢ"=>"ЗЯ", "GIÃ"=>"ЗЯ", "GIẠ"=>"ЗЯ",
-        "GIO"=>"ЗЁ", "GIÒ"=>"ЗЁ", "GIÓ"=>"ЗЁ",
-        "GIỎ"=>"ЗЁ", "GIÕ"=>"ЗЁ", "GIỌ"=>"ЗЁ",
-        "GIU"=>"ЗЮ", "GIÙ"=>"ЗЮ", "GIÚ"=>"ЗЮ",
-        "GIỦ"=>"ЗЮ", "GIŨ"=>"ЗЮ", "GIỤ"=>"ЗЮ",
-        "NHA"=>"НЯ", "NHÀ"=>"НЯ", "NHÁ"=>"НЯ",
-        "NHẢ"=>"НЯ", "NHÃ"=>"НЯ", "NHẠ"=>"НЯ",
-        "NHO"=>"НЁ", "NHÒ"=>"НЁ", "NHÓ"=>"НЁ",
-        "NHỎ"=>"НЁ", "NHÕ"=>"НЁ", "NHỌ"=>"НЁ",
-        "NHU"=>"НЮ", "NHÙ"=>"НЮ", "NHÚ"=>"НЮ",
-        "NHỦ"=>"НЮ", "NHŨ"=>"НЮ", "NHỤ"=>"НЮ",
-        "NHI"=>"НИ", "NHÌ"=>"НИ", "NHÍ"=>"НИ",
-        "NHỈ"=>"НИ", "NHĨ"=>"НИ", "NHỊ"=>"НИ",
+        "PALATALIZATION PATTERNS" {
+            // Established forms keep "Ш" (Hồ Chí Minh -> Хо Ши Мин)
+            "CHI"=>"ТИ"|"ШИ", "CHÌ"=>"ТИ"|"ШИ", "CHÍ"=>"ТИ"|"ШИ",
+            "CHỈ"=>"ТИ"|"ШИ", "CHĨ"=>"ТИ"|"ШИ", "CHỊ"=>"ТИ"|"ШИ",
+            "CHE"=>"ТЕ", "CHÈ"=>"ТЕ", "CHÉ"=>"ТЕ",
+            "CHẺ"=>"ТЕ", "CHẼ"=>"ТЕ", "CHẸ"=>"ТЕ",
+            "CHÊ"=>"ТЕ", "CHỀ"=>"ТЕ", "CHẾ"=>"ТЕ",
+            "CHỂ"=>"ТЕ", "CHỄ"=>"ТЕ", "CHỆ"=>"ТЕ",
+            "GIA"=>"ЗЯ", "GIÀ"=>"ЗЯ", "GIÁ"=>"ЗЯ",
+            "GIẢ"=>"ЗЯ", "GIÃ"=>"ЗЯ", "GIẠ"=>"ЗЯ",
+            "GIO"=>"ЗЁ", "GIÒ"=>"ЗЁ", "GIÓ"=>"ЗЁ",
+            "GIỎ"=>"ЗЁ", "GIÕ"=>"ЗЁ", "GIỌ"=>"ЗЁ",
+            "GIU"=>"ЗЮ", "GIÙ"=>"ЗЮ", "GIÚ"=>"ЗЮ",
+            "GIỦ"=>"ЗЮ", "GIŨ"=>"ЗЮ", "GIỤ"=>"ЗЮ",
+            "NHA"=>"НЯ", "NHÀ"=>"НЯ", "NHÁ"=>"НЯ",
+            "NHẢ"=>"НЯ", "NHÃ"=>"НЯ", "NHẠ"=>"НЯ",
+            "NHO"=>"НЁ", "NHÒ"=>"НЁ", "NHÓ"=>"НЁ",
+            "NHỎ"=>"НЁ", "NHÕ"=>"НЁ", "NHỌ"=>"НЁ",
+            "NHU"=>"НЮ", "NHÙ"=>"НЮ", "NHÚ"=>"НЮ",
+            "NHỦ"=>"НЮ", "NHŨ"=>"НЮ", "NHỤ"=>"НЮ",
+            "NHI"=>"НИ", "NHÌ"=>"НИ", "NHÍ"=>"НИ",
+            "NHỈ"=>"НИ", "NHĨ"=>"НИ", "NHỊ"=>"НИ",
+        },
+    },
+    post_rules: {
+        // No initial soft sign (Iên → Иен), no doubled signs at the seams
+        "|ЬЕ" => "ИЕ",
+        "ЙЙ" => "Й",
+        "ЬЬ" => "Ь",
+    },
+    markers: {
+        // Syllable shapes
+        "|NGUY", "|NGH", "|NG", "|TR", "|GI", "UY", "ƯƠ", "IÊ",
+        // Frequent family names, unaccented and accented
+        "|NGUYEN|", "|TRAN|", "|LE|", "|PHAM|", "|HOANG|", "|HUYNH|",
+        "|VU|", "|DANG|", "|BUI|", "|DO|", "|NGO|", "|DUONG|",
+        "|NGUYỄN|", "|TRẦN|", "|LÊ|", "|PHẠM|", "|HOÀNG|", "|HUỲNH|",
+        "|VŨ|", "|ĐẶNG|", "|BÙI|", "|ĐỖ|", "|NGÔ|", "|DƯƠNG|",
+        // Middle names
+        "|VAN|", "|THI|", "|DUC|", "|QUOC|",
+        "|VĂN|", "|THỊ|", "|ĐỨC|", "|QUỐC|",
+    },
+    names: {
+        order: FamilyFirst,
+        honorifics: { "Ông", "Bà" },
+        genders: {
+            "Thị" => Female, "Thi" => Female,
+            "Văn" => Male, "Van" => Male,
+            "Ông" => Male, "Bà" => Female,
+        },
+        stress: Final,
     },
     mappings: {
         vec![
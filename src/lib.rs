@@ -0,0 +1,45 @@
+//! Cyrillify: transcription of foreign names into Russian Cyrillic.
+//!
+//! The engine is shared by the desktop application and any other consumer:
+//!
+//! ```
+//! use cyrillify::{TranscriberEnum, TranscriberTrait};
+//!
+//! let transcriber = TranscriberEnum::find("vie").unwrap();
+//! assert_eq!(transcriber.transcribe("Tô Lâm"), "То Лам");
+//! ```
+//!
+//! Languages can also be added or overridden at runtime from rule files
+//! through [`Registry`].
+
+pub mod batch;
+pub mod casing;
+pub mod context;
+pub mod coverage;
+pub mod custom;
+pub mod declension;
+pub mod detect;
+pub mod dictionary;
+pub mod langs;
+pub mod name;
+pub mod normalize;
+pub mod orthography;
+pub mod overrides;
+pub mod pipeline;
+pub mod registry;
+pub mod reverse;
+pub mod spelling;
+pub mod stress;
+pub mod trace;
+pub mod transcriber;
+pub mod trie;
+pub mod validate;
+pub mod variants;
+
+#[cfg(test)]
+mod tests;
+
+pub use overrides::Overrides;
+pub use registry::Registry;
+pub use trace::{Origin, Segment, Trace};
+pub use transcriber::{Mapping, TranscriberEnum, TranscriberTrait};
@@ -0,0 +1,214 @@
+//! Structured personal names.
+//!
+//! Every language tells where its family name stands (Vietnamese names are
+//! surname-first, Thai names surname-last, Burmese names have none) and
+//! which leading words are honorifics (`U`, `Daw`, `Khun`) and which parts
+//! tell the gender (`Thị`, `Daw`, `Nangsao`). Parsed names are formatted in
+//! the order of Russian documents: family name, given names.
+
+use serde::Deserialize;
+
+use crate::normalize;
+use crate::orthography::Orthography;
+use crate::overrides::Overrides;
+use crate::stress::Stress;
+use crate::transcriber::TranscriberTrait;
+
+/// Position of the family name
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Order {
+    FamilyFirst,
+    #[default]
+    FamilyLast,
+    /// No family names, every part is given
+    GivenOnly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Gender {
+    Male,
+    Female,
+}
+
+/// Naming convention of a language
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Convention {
+    pub order: Order,
+    /// Titles preceding names, matched case-insensitively
+    pub honorifics: &'static [&'static str],
+    /// Name parts telling the gender (middle names, titles)
+    pub genders: &'static [(&'static str, Gender)],
+    /// Stress placement in transcribed names
+    pub stress: Stress,
+}
+
+/// Role of a name part
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    Honorific,
+    Family,
+    Given,
+    /// Given name between the first and the last part (Vietnamese `Văn`)
+    Middle,
+}
+
+/// Name part with its transcription
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part {
+    pub role: Role,
+    pub source: String,
+    pub text: String,
+}
+
+/// Name split into classified parts, in source order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Name {
+    pub parts: Vec<Part>,
+}
+
+impl Name {
+    /// Transcribed parts in source order
+    pub fn text(&self) -> String {
+        self.texts(|_| true)
+    }
+
+    /// Transcribed family name (none for languages without one)
+    pub fn family(&self) -> Option<&str> {
+        self.parts
+            .iter()
+            .find(|part| part.role == Role::Family)
+            .map(|part| part.text.as_str())
+    }
+
+    /// Transcribed given and middle names in source order
+    pub fn given(&self) -> String {
+        self.texts(|role| matches!(role, Role::Given | Role::Middle))
+    }
+
+    /// Transcribed honorifics
+    pub fn honorifics(&self) -> String {
+        self.texts(|role| role == Role::Honorific)
+    }
+
+    /// Name in the order of Russian documents (honorifics dropped)
+    pub fn format(&self) -> String {
+        match self.family() {
+            Some(family) => format!("{family} {}", self.given()),
+            None => self.given(),
+        }
+    }
+
+    fn texts(&self, f: impl Fn(Role) -> bool) -> String {
+        let texts: Vec<_> = self
+            .parts
+            .iter()
+            .filter(|part| f(part.role))
+            .map(|part| part.text.as_str())
+            .collect();
+        texts.join(" ")
+    }
+}
+
+/// Gender told by name parts (unknown without or with conflicting ones)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inference {
+    pub gender: Option<Gender>,
+    /// Source parts that told the gender
+    pub evidence: Vec<(String, Gender)>,
+}
+
+/// Infers gender from the parts the language marks it with
+pub fn infer_gender<T: TranscriberTrait + ?Sized>(
+    transcriber: &T,
+    input: &str,
+) -> Inference {
+    let genders = transcriber.get_name_convention().genders;
+    let evidence: Vec<(String, Gender)> = input
+        .split_whitespace()
+        .filter_map(|part| {
+            let key = normalize::normalize(part).text.to_uppercase();
+            let (_, gender) = genders
+                .iter()
+                .find(|(marker, _)| marker.to_uppercase() == key)?;
+            Some((part.to_string(), *gender))
+        })
+        .collect();
+
+    let mut genders = evidence.iter().map(|(_, gender)| *gender);
+    let gender =
+        genders.next().filter(|first| genders.all(|gender| gender == *first));
+    Inference { gender, evidence }
+}
+
+/// Splits input into parts classified by the convention of the language,
+/// transcribed in the spelling conventions given
+pub fn parse<T: TranscriberTrait + ?Sized>(
+    transcriber: &T,
+    input: &str,
+    overrides: &Overrides,
+    orthography: &Orthography,
+) -> Name {
+    let sources: Vec<&str> = input.split_whitespace().collect();
+
+    // Whole name first (dictionaries span several parts), part by part if
+    // its words do not line up with the input
+    let output = transcriber.trace_in(input, overrides, orthography).output;
+    let words: Vec<&str> = output.split_whitespace().collect();
+    let texts: Vec<String> = if words.len() == sources.len() {
+        words.into_iter().map(String::from).collect()
+    } else {
+        sources
+            .iter()
+            .map(|part| {
+                transcriber.trace_in(part, overrides, orthography).output
+            })
+            .collect()
+    };
+
+    let roles = classify(transcriber.get_name_convention(), &sources);
+    let parts = sources
+        .into_iter()
+        .zip(texts)
+        .zip(roles)
+        .map(|((source, text), role)| Part {
+            role,
+            source: source.to_string(),
+            text,
+        })
+        .collect();
+    Name { parts }
+}
+
+// Leading honorifics (at least one part is left), then the name proper
+fn classify(convention: Convention, parts: &[&str]) -> Vec<Role> {
+    let is_honorific = |part: &str| {
+        let part = normalize::normalize(part).text.to_uppercase();
+        convention
+            .honorifics
+            .iter()
+            .any(|honorific| honorific.to_uppercase() == part)
+    };
+    let honorifics = parts
+        .iter()
+        .take(parts.len().saturating_sub(1))
+        .take_while(|part| is_honorific(part))
+        .count();
+
+    let mut roles = vec![Role::Honorific; honorifics];
+    let len = parts.len() - honorifics;
+    for i in 0..len {
+        roles.push(match convention.order {
+            // Single part is a given name
+            _ if len == 1 => Role::Given,
+            Order::GivenOnly => Role::Given,
+            Order::FamilyFirst if i == 0 => Role::Family,
+            Order::FamilyFirst if i == len - 1 => Role::Given,
+            Order::FamilyLast if i == len - 1 => Role::Family,
+            Order::FamilyLast if i == 0 => Role::Given,
+            _ => Role::Middle,
+        });
+    }
+    roles
+}
@@ -0,0 +1,108 @@
+//! Input normalization before transcription.
+//!
+//! Input is brought to NFC, so decomposed diacritics (base letter plus
+//! combining marks, as produced by macOS and many PDFs) match rule keys.
+//! Invisible format characters (zero-width, bidi controls) are dropped and
+//! look-alike apostrophes become `'`.
+
+use std::ops::Range;
+
+use unicode_normalization::{
+    IsNormalized, UnicodeNormalization, is_nfc_quick,
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Normalized text leading back to the original input
+pub struct Normalized {
+    pub text: String,
+    // Input offset of every text byte (plus the end)
+    offsets: Vec<usize>,
+}
+
+impl Normalized {
+    /// Text as it is
+    pub fn identity(input: &str) -> Normalized {
+        Normalized {
+            text: input.to_string(),
+            offsets: (0..=input.len()).collect(),
+        }
+    }
+
+    /// Input span of text span (spans should not split graphemes)
+    pub fn source(&self, range: Range<usize>) -> Range<usize> {
+        self.offsets[range.start]..self.offsets[range.end]
+    }
+
+    /// Rewrite of the text leading back to the original input
+    pub fn then(&self, rewrite: Normalized) -> Normalized {
+        let offsets =
+            rewrite.offsets.iter().map(|&i| self.offsets[i]).collect();
+        Normalized {
+            text: rewrite.text,
+            offsets,
+        }
+    }
+}
+
+/// Checks if normalization would leave input as it is
+pub fn is_normalized(input: &str) -> bool {
+    is_nfc_quick(input.chars()) == IsNormalized::Yes
+        && !input.chars().any(|c| is_invisible(c) || apostrophe(c) != c)
+}
+
+/// Normalizes input grapheme by grapheme
+pub fn normalize(input: &str) -> Normalized {
+    if is_normalized(input) {
+        return Normalized::identity(input);
+    }
+    map(input, |grapheme| {
+        grapheme
+            .chars()
+            .filter(|&c| !is_invisible(c))
+            .map(apostrophe)
+            .nfc()
+            .collect()
+    })
+}
+
+/// Rewrites input grapheme by grapheme (romanization of other scripts)
+pub fn map(input: &str, mut f: impl FnMut(&str) -> String) -> Normalized {
+    let mut text = String::with_capacity(input.len());
+    let mut offsets = Vec::with_capacity(input.len() + 1);
+
+    // Dropped graphemes are attached to the following one
+    let mut start = 0;
+    for (i, grapheme) in input.grapheme_indices(true) {
+        let len = text.len();
+        text += &f(grapheme);
+        if text.len() > len {
+            offsets.resize(text.len(), start);
+            start = i + grapheme.len();
+        }
+    }
+    offsets.push(input.len());
+
+    Normalized { text, offsets }
+}
+
+// Zero-width and bidirectional formatting characters
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{00AD}'
+            | '\u{FEFF}'
+    )
+}
+
+// Typographic quotes used as apostrophes (modifier letters such as `ʼ`
+// and `ʻ` are letters of their own and stay)
+fn apostrophe(c: char) -> char {
+    match c {
+        '\u{2018}' | '\u{2019}' | '\u{201B}' | '\u{2032}' | '\u{00B4}' => '\'',
+        c => c,
+    }
+}
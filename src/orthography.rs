@@ -0,0 +1,144 @@
+//! Output spelling conventions.
+//!
+//! Rule tables spell names one way (`Ё`, `Й` after vowels, final `Ь`);
+//! documents often want another. Conventions are applied to the finished
+//! transcription or by the `Orthography` stage of the pipeline, so the
+//! tables stay as they are: user replacements of
+//! letter sequences first, then `Ё` folding, `Й`/`И` after vowels and the
+//! final soft sign.
+
+use crate::casing;
+use crate::pipeline::Rewrite;
+use crate::spelling::is_vowel;
+use crate::stress::MARK;
+
+/// Spelling of `Й` and `И` after a vowel
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AfterVowel {
+    /// As the rules spell it
+    #[default]
+    Keep,
+    /// `Й` (Хоай)
+    Short,
+    /// `И` (Хоаи)
+    Full,
+}
+
+/// Spelling conventions of the output (default: as the rules spell it)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Orthography {
+    /// `Ё` written as `Е`
+    pub fold_yo: bool,
+    pub after_vowel: AfterVowel,
+    /// `Ь` dropped at word end (Линь → Лин)
+    pub drop_final_soft_sign: bool,
+    /// Forbidden letter sequences and their replacements, matched
+    /// case-insensitively (`ЬЁ` → `Ё`)
+    pub replacements: Vec<(String, String)>,
+}
+
+impl Orthography {
+    /// Respells transcribed text as a whole (the `Orthography` stage
+    /// leaves text copied without a rule as it is)
+    pub fn apply(&self, text: &str) -> String {
+        self.rewrites()
+            .iter()
+            .fold(text.to_string(), |text, rewrite| respell(&text, rewrite))
+    }
+
+    /// Rewrites of text in order: replacements, then the conventions
+    pub(crate) fn rewrites(&self) -> Vec<Box<Rewrite<'_>>> {
+        let mut rewrites: Vec<Box<Rewrite>> = self
+            .replacements
+            .iter()
+            .filter(|(from, _)| !from.is_empty())
+            .map(|(from, to)| -> Box<Rewrite> {
+                Box::new(move |text, pos| replace(text, pos, from, to))
+            })
+            .collect();
+        if self.fold_yo
+            || self.after_vowel != AfterVowel::Keep
+            || self.drop_final_soft_sign
+        {
+            rewrites.push(Box::new(|text, pos| self.convention(text, pos)));
+        }
+        rewrites
+    }
+
+    // Respelling of the letter at position by the conventions
+    fn convention(&self, text: &str, pos: usize) -> Option<(usize, String)> {
+        let c = text[pos..].chars().next()?;
+        let end = pos + c.len_utf8();
+        // Stress marks stay with their vowel
+        let previous = text[..pos].chars().rev().find(|&c| c != MARK);
+        let after_vowel = previous.is_some_and(is_vowel);
+        let word_end =
+            text[end..].chars().next().is_none_or(|c| !c.is_alphabetic());
+        let letter = match c {
+            'Ё' | 'ё' if self.fold_yo => recase('е', c),
+            'Й' | 'й'
+                if after_vowel && self.after_vowel == AfterVowel::Full =>
+            {
+                recase('и', c)
+            }
+            'И' | 'и'
+                if after_vowel && self.after_vowel == AfterVowel::Short =>
+            {
+                recase('й', c)
+            }
+            'Ь' | 'ь' if word_end && self.drop_final_soft_sign => {
+                return Some((end, String::new()));
+            }
+            _ => return None,
+        };
+        Some((end, letter.to_string()))
+    }
+}
+
+// Rewrites text left to right, copying letters without a rewrite
+fn respell(text: &str, rewrite: &Rewrite) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut pos = 0;
+    while let Some(c) = text[pos..].chars().next() {
+        match rewrite(text, pos) {
+            Some((end, respelled)) => {
+                output += &respelled;
+                pos = end;
+            }
+            None => {
+                output.push(c);
+                pos += c.len_utf8();
+            }
+        }
+    }
+    output
+}
+
+// Sequence at position in any case, replacement spelled in the case found
+fn replace(
+    text: &str,
+    pos: usize,
+    from: &str,
+    to: &str,
+) -> Option<(usize, String)> {
+    let mut end = pos;
+    let mut chars = text[pos..].chars();
+    for f in from.chars() {
+        let c = chars.next()?;
+        if !c.to_lowercase().eq(f.to_lowercase()) {
+            return None;
+        }
+        end += c.len_utf8();
+    }
+    let case = casing::record_case(&text[pos..end]);
+    Some((end, casing::reapply_case(to, case)))
+}
+
+// Lowercase letter in the case of original
+fn recase(letter: char, original: char) -> char {
+    if original.is_uppercase() {
+        letter.to_uppercase().next().unwrap_or(letter)
+    } else {
+        letter
+    }
+}
@@ -0,0 +1,161 @@
+//! User override dictionary: spellings agreed on for specific people.
+//!
+//! Overrides are kept per language code and consulted before exceptions
+//! and rules. They are stored as TOML, one table per language:
+//!
+//! ```toml
+//! [vie]
+//! "Nguyễn Văn Linh" = "Нгуен Ван Лин"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dictionary;
+use crate::normalize;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "cannot access overrides: {err}"),
+            Error::Parse(err) => write!(f, "malformed overrides: {err}"),
+            Error::Serialize(err) => {
+                write!(f, "cannot serialize overrides: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Parse(err)
+    }
+}
+
+impl From<toml::ser::Error> for Error {
+    fn from(err: toml::ser::Error) -> Self {
+        Error::Serialize(err)
+    }
+}
+
+/// Spellings by language code (from -> to)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Overrides {
+    langs: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Overrides {
+    /// Parses overrides file contents
+    pub fn from_toml(source: &str) -> Result<Self, Error> {
+        let overrides: Self = toml::from_str(source)?;
+
+        // Language codes are case insensitive
+        let mut langs: BTreeMap<String, BTreeMap<String, String>> =
+            BTreeMap::new();
+        for (lang_code, entries) in overrides.langs {
+            langs.entry(lang_code.to_lowercase()).or_default().extend(
+                entries.into_iter().map(|(from, to)| (key(&from), to)),
+            );
+        }
+        Ok(Self { langs })
+    }
+
+    /// Serializes overrides into file contents
+    pub fn to_toml(&self) -> Result<String, Error> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Reads overrides file (missing one is empty)
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(source) => Self::from_toml(&source),
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Writes overrides file, creating its directory
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+
+    /// Sets spelling of name for language
+    pub fn insert(&mut self, lang_code: &str, from: &str, to: &str) {
+        self.langs
+            .entry(lang_code.to_lowercase())
+            .or_default()
+            .insert(key(from), to.trim().to_string());
+    }
+
+    /// Removes spelling of name for language
+    pub fn remove(&mut self, lang_code: &str, from: &str) -> Option<String> {
+        let lang_code = lang_code.to_lowercase();
+        let entries = self.langs.get_mut(&lang_code)?;
+        let removed = entries.remove(&key(from));
+        if entries.is_empty() {
+            self.langs.remove(&lang_code);
+        }
+        removed
+    }
+
+    /// Spelling of name for language, if overridden
+    pub fn get(&self, lang_code: &str, from: &str) -> Option<&str> {
+        let entries = self.langs.get(&lang_code.to_lowercase())?;
+        entries.get(&key(from)).map(String::as_str)
+    }
+
+    /// Iterates over overrides of language
+    pub fn iter(&self, lang_code: &str) -> impl Iterator<Item = (&str, &str)> {
+        self.langs
+            .get(&lang_code.to_lowercase())
+            .into_iter()
+            .flatten()
+            .map(|(from, to)| (from.as_str(), to.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.langs.is_empty()
+    }
+
+    /// Longest override of language starting the text (byte length, value)
+    pub fn find(&self, lang_code: &str, text: &str) -> Option<(usize, &str)> {
+        self.iter(lang_code)
+            .filter_map(|(from, to)| {
+                Some((dictionary::match_phrase(text, from)?, to))
+            })
+            .max_by_key(|(len, _)| *len)
+    }
+}
+
+// Names are stored normalized like input
+fn key(from: &str) -> String {
+    normalize::normalize(from.trim()).text
+}
@@ -0,0 +1,607 @@
+//! Transcription pipeline.
+//!
+//! Input goes through the stages its language declares, in order. Input
+//! stages (normalization, romanization or syllable parsing of scripts the
+//! rules cannot read) rewrite the text keeping offsets to the input, the
+//! dictionaries claim whole names, the rule table transcribes every word
+//! into uppercased fragments, and output stages rewrite the fragments
+//! before the case of the source is restored.
+//!
+//! Input is split into words by the first stage acting on words, so input
+//! stages and dictionaries must be declared before it, and dictionaries
+//! keep offsets into the rewritten input, so input stages come before them
+//! (see `in_order`).
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use serde::Deserialize;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::casing::{self, Case};
+use crate::dictionary;
+use crate::normalize::{self, Normalized};
+use crate::orthography::Orthography;
+use crate::overrides::Overrides;
+use crate::trace::{Origin, Trace};
+use crate::transcriber::{self, Rule, TranscriberTrait};
+use crate::trie::Trie;
+
+/// Stage of the pipeline
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Stage {
+    /// NFC, invisible characters dropped, apostrophes unified
+    Normalize,
+    /// Input rewrite of the language (see `normalize::map`)
+    #[serde(skip)]
+    Input(fn(&str) -> Normalized),
+    /// User overrides, then whole-name exceptions
+    Exceptions,
+    /// Main rule table, word by word
+    Rules,
+    /// Post rules on the output of every word
+    PostRules,
+    /// Spelling conventions of the output (`transcribe_in`)
+    Orthography,
+    /// Case of the source reapplied (output stays uppercased without it)
+    Casing,
+}
+
+/// Stages of languages declaring none
+pub const DEFAULT: &[Stage] = &[
+    Stage::Normalize,
+    Stage::Exceptions,
+    Stage::Rules,
+    Stage::PostRules,
+    Stage::Orthography,
+    Stage::Casing,
+];
+
+/// Checks that input stages precede dictionaries and both precede the word
+/// stages
+pub const fn in_order(stages: &[Stage]) -> bool {
+    let mut found = false;
+    let mut words = false;
+    let mut i = 0;
+    while i < stages.len() {
+        match stages[i] {
+            Stage::Normalize | Stage::Input(_) => {
+                if found || words {
+                    return false;
+                }
+            }
+            Stage::Exceptions => {
+                if words {
+                    return false;
+                }
+                found = true;
+            }
+            _ => words = true,
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Rewrite at text position: end of the rewritten span and its spelling
+pub(crate) type Rewrite<'a> =
+    dyn Fn(&str, usize) -> Option<(usize, String)> + 'a;
+
+// Output of a word fragment (source span within the word)
+struct Fragment {
+    source: Range<usize>,
+    origin: Origin,
+    text: Cow<'static, str>,
+    case: Option<Case>,
+}
+
+// Word or text between words (separators, dictionary entries), separators
+// without fragments as they are copied from the input
+struct Piece {
+    span: Range<usize>,
+    word: bool,
+    fragments: Vec<Fragment>,
+}
+
+struct Pipeline<'a> {
+    // Input as given until an input stage rewrites it
+    source: &'a str,
+    input: Option<Normalized>,
+    // Dictionary entries found in the input
+    found: Vec<(Range<usize>, String, Origin)>,
+    pieces: Option<Vec<Piece>>,
+    // Post rules applied to the words
+    post_rules: Vec<&'static Rule>,
+}
+
+/// Runs input through the stages of the language
+pub fn run<T: TranscriberTrait + ?Sized>(
+    transcriber: &T,
+    input: &str,
+    overrides: &Overrides,
+    orthography: &Orthography,
+) -> Trace {
+    process(transcriber, input, overrides, orthography, &[]).trace()
+}
+
+/// Runs input through the stages of the language, output only
+pub fn transcribe<T: TranscriberTrait + ?Sized>(
+    transcriber: &T,
+    input: &str,
+    overrides: &Overrides,
+    orthography: &Orthography,
+) -> String {
+    process(transcriber, input, overrides, orthography, &[]).output()
+}
+
+/// Runs input through the stages of the language with alternative readings
+/// picked for the rules that have them (in output order, `None` for the
+/// first reading), output only
+pub(crate) fn transcribe_picked<T: TranscriberTrait + ?Sized>(
+    transcriber: &T,
+    input: &str,
+    overrides: &Overrides,
+    orthography: &Orthography,
+    picks: &[Option<usize>],
+) -> String {
+    process(transcriber, input, overrides, orthography, picks).output()
+}
+
+fn process<'a, T: TranscriberTrait + ?Sized>(
+    transcriber: &T,
+    input: &'a str,
+    overrides: &Overrides,
+    orthography: &Orthography,
+    picks: &[Option<usize>],
+) -> Pipeline<'a> {
+    let mut pipeline = Pipeline {
+        source: input,
+        input: None,
+        found: Vec::new(),
+        pieces: None,
+        post_rules: Vec::new(),
+    };
+
+    for stage in transcriber.get_stages() {
+        match stage {
+            Stage::Normalize => {
+                if !normalize::is_normalized(pipeline.text()) {
+                    let text = normalize::normalize(pipeline.text());
+                    pipeline.rewrite_input(text);
+                }
+            }
+            Stage::Input(rewrite) => {
+                let text = rewrite(pipeline.text());
+                pipeline.rewrite_input(text);
+            }
+            Stage::Exceptions => pipeline.find(transcriber, overrides),
+            Stage::Rules => {
+                pipeline.transcribe(transcriber);
+                if !picks.is_empty() {
+                    pipeline.pick(picks);
+                }
+            }
+            Stage::PostRules => {
+                let post = transcriber.get_post_trie();
+                let mut applied = Vec::new();
+                if !post.is_empty() {
+                    pipeline.rewrite(|text, span| {
+                        let (end, rule) = post
+                            .longest_match(text, span.start)
+                            .filter(|&(end, _)| {
+                                end > span.start && end <= span.end
+                            })?;
+                        applied.push(rule);
+                        Some((end, rule.value.to_string()))
+                    });
+                }
+                pipeline.post_rules.extend(applied);
+            }
+            Stage::Orthography if *orthography == Orthography::default() => {}
+            Stage::Orthography => {
+                for rewrite in orthography.rewrites() {
+                    pipeline.rewrite(|text, span| rewrite(text, span.start));
+                }
+                // Dictionary spellings are respelled whole
+                for piece in pipeline.pieces() {
+                    for fragment in &mut piece.fragments {
+                        if matches!(
+                            fragment.origin,
+                            Origin::Override | Origin::Exception(_)
+                        ) {
+                            fragment.text =
+                                orthography.apply(&fragment.text).into();
+                        }
+                    }
+                }
+            }
+            Stage::Casing => pipeline.reapply_case(),
+        }
+    }
+
+    pipeline
+}
+
+impl Pipeline<'_> {
+    // Input text as the stages so far left it
+    fn text(&self) -> &str {
+        text(self.source, &self.input)
+    }
+
+    // Input span of text span
+    fn source(&self, range: Range<usize>) -> Range<usize> {
+        match &self.input {
+            Some(input) => input.source(range),
+            None => range,
+        }
+    }
+
+    // Replaces the input text, keeping the way back to the original input
+    fn rewrite_input(&mut self, text: Normalized) {
+        self.input = Some(match &self.input {
+            Some(input) => input.then(text),
+            None => text,
+        });
+    }
+
+    // Dictionary entries at the segment starts, the longest first
+    fn find<T: TranscriberTrait + ?Sized>(
+        &mut self,
+        transcriber: &T,
+        overrides: &Overrides,
+    ) {
+        let input = text(self.source, &self.input);
+        let lang_code = transcriber.get_lang_code();
+        let exceptions = transcriber.get_exceptions();
+        if exceptions.is_empty()
+            && (overrides.is_empty()
+                || overrides.iter(lang_code).next().is_none())
+        {
+            return;
+        }
+        let mut covered = 0;
+
+        for (offset, _) in transcriber::segments(input) {
+            // Skip words already covered by a dictionary
+            if offset < covered {
+                continue;
+            }
+
+            // Whole-name dictionaries take precedence over rules
+            let text = &input[offset..];
+            let found = overrides
+                .find(lang_code, text)
+                .map(|(len, to)| (len, to, Origin::Override))
+                .or_else(|| {
+                    dictionary::find(exceptions, text, |e| e.from).map(
+                        |(len, entry)| {
+                            (len, entry.to, Origin::Exception(entry))
+                        },
+                    )
+                });
+            if let Some((len, to, origin)) = found {
+                let source = offset..offset + len;
+                let output = dictionary::recase(&input[source.clone()], to);
+                self.found.push((source, output, origin));
+                covered = offset + len;
+            }
+        }
+    }
+
+    // Input split into words and the text between them, words copied
+    // grapheme by grapheme until the rules transcribe them
+    fn pieces(&mut self) -> &mut Vec<Piece> {
+        let input = text(self.source, &self.input);
+        self.pieces.get_or_insert_with(|| split(input, &self.found, None))
+    }
+
+    // Transcribes uppercased words (offsets lead back to the word)
+    fn transcribe<T: TranscriberTrait + ?Sized>(&mut self, transcriber: &T) {
+        let trie = transcriber.get_trie();
+        let input = text(self.source, &self.input);
+
+        match &mut self.pieces {
+            None => self.pieces = Some(split(input, &self.found, Some(trie))),
+            Some(pieces) => {
+                for piece in pieces.iter_mut().filter(|piece| piece.word) {
+                    let word = &input[piece.span.clone()];
+                    piece.fragments = fragments(word, Some(trie));
+                }
+            }
+        }
+    }
+
+    // Replaces readings of the rules with alternatives by the ones picked
+    fn pick(&mut self, picks: &[Option<usize>]) {
+        let fragments = self
+            .pieces()
+            .iter_mut()
+            .flat_map(|piece| &mut piece.fragments)
+            .filter_map(|fragment| match fragment.origin {
+                Origin::Rule(rule) if !rule.alternatives.is_empty() => {
+                    Some((fragment, rule))
+                }
+                _ => None,
+            });
+        for ((fragment, rule), pick) in fragments.zip(picks) {
+            if let Some(reading) = pick {
+                fragment.text = Cow::Borrowed(rule.alternatives[*reading]);
+            }
+        }
+    }
+
+    // Rewrites the output of every word, matching within runs of rule
+    // fragments only (unmatched text stays as it is, the rewrite gets the
+    // span left of the run); replacements go to the fragment the match
+    // starts in
+    fn rewrite(
+        &mut self,
+        mut rewrite: impl FnMut(&str, Range<usize>) -> Option<(usize, String)>,
+    ) {
+        // Buffers reused word by word
+        let mut text = String::new();
+        let mut spans = Vec::new();
+        let mut run_ends = Vec::new();
+
+        for piece in self.pieces().iter_mut().filter(|piece| piece.word) {
+            let fragments = &mut piece.fragments;
+            text.clear();
+            spans.clear();
+            run_ends.clear();
+
+            // Output span of every fragment and the end of its rule run
+            for fragment in fragments.iter() {
+                let start = text.len();
+                text += &fragment.text;
+                spans.push(start..text.len());
+            }
+            run_ends.resize(fragments.len(), 0);
+            let mut run_end = text.len();
+            for i in (0..fragments.len()).rev() {
+                match fragments[i].origin {
+                    Origin::Rule(_) => run_ends[i] = run_end,
+                    _ => run_end = spans[i].start,
+                }
+            }
+
+            // Rewritten spans with the fragment they start in
+            let mut edits = Vec::new();
+            let mut graphemes = text.grapheme_indices(true).peekable();
+            let mut i = 0;
+            let mut pos = 0;
+            while pos < text.len() {
+                while spans[i].end <= pos {
+                    i += 1;
+                }
+                let rewritten = match fragments[i].origin {
+                    Origin::Rule(_) => rewrite(&text, pos..run_ends[i])
+                        .filter(|&(end, _)| end > pos && end <= run_ends[i]),
+                    _ => None,
+                };
+                pos = match rewritten {
+                    Some((end, output)) => {
+                        edits.push((i, pos..end, output));
+                        end
+                    }
+                    // Next grapheme
+                    None => {
+                        while graphemes.next_if(|&(j, _)| j <= pos).is_some() {
+                        }
+                        graphemes.peek().map_or(text.len(), |&(j, _)| j)
+                    }
+                };
+            }
+            if edits.is_empty() {
+                continue;
+            }
+
+            // Text between the edits stays with its fragments
+            let mut outputs = vec![String::new(); fragments.len()];
+            let copy = |outputs: &mut Vec<String>, range: Range<usize>| {
+                for (output, span) in outputs.iter_mut().zip(&spans) {
+                    let start = span.start.max(range.start);
+                    let end = span.end.min(range.end);
+                    if start < end {
+                        *output += &text[start..end];
+                    }
+                }
+            };
+            let mut copied = 0;
+            for (i, span, output) in edits {
+                copy(&mut outputs, copied..span.start);
+                outputs[i] += &output;
+                copied = span.end;
+            }
+            copy(&mut outputs, copied..text.len());
+
+            for (fragment, output) in fragments.iter_mut().zip(outputs) {
+                fragment.text = output.into();
+            }
+        }
+    }
+
+    // Reapplies case of the source fragments
+    fn reapply_case(&mut self) {
+        self.pieces();
+        let input = text(self.source, &self.input);
+
+        for piece in self.pieces.iter_mut().flatten() {
+            if !piece.word {
+                continue;
+            }
+            let word = &input[piece.span.clone()];
+            // Capital of a silent fragment passes to the next one
+            let mut carried = None;
+
+            for fragment in &mut piece.fragments {
+                let source = fragment.source.clone();
+                match fragment.origin {
+                    Origin::Rule(_) => {
+                        let case = casing::record_fragment_case(word, source);
+                        let case = match case {
+                            Case::Lower => carried.take().unwrap_or(case),
+                            case => case,
+                        };
+                        if fragment.text.is_empty() && case != Case::Lower {
+                            carried = Some(case);
+                        }
+                        fragment.text =
+                            casing::reapply_case(&fragment.text, case).into();
+                        fragment.case = Some(case);
+                    }
+                    // Unmatched text keeps its case (e.g. Cyrillic input)
+                    _ => {
+                        carried = None;
+                        fragment.text = word[source].to_string().into();
+                    }
+                }
+            }
+        }
+    }
+
+    // Output of the pieces
+    fn output(mut self) -> String {
+        self.pieces();
+        let input = text(self.source, &self.input);
+        let mut output = String::with_capacity(input.len() * 2);
+        for piece in self.pieces.iter().flatten() {
+            if piece.fragments.is_empty() {
+                output += &input[piece.span.clone()];
+            }
+            for fragment in &piece.fragments {
+                output += &fragment.text;
+            }
+        }
+        output
+    }
+
+    // Trace of the pieces, spans led back to the input
+    fn trace(mut self) -> Trace {
+        self.pieces();
+        let mut trace = Trace {
+            post_rules: std::mem::take(&mut self.post_rules),
+            ..Trace::default()
+        };
+        trace.output.reserve(self.source.len() * 2);
+
+        let input = text(self.source, &self.input);
+        for piece in self.pieces.iter().flatten() {
+            if piece.fragments.is_empty() {
+                let source = self.source(piece.span.clone());
+                let text = &input[piece.span.clone()];
+                trace.push(source, text, Origin::Separator, None);
+            }
+            for fragment in &piece.fragments {
+                let start = piece.span.start;
+                let source =
+                    start + fragment.source.start..start + fragment.source.end;
+                let source = self.source(source);
+                trace.push(
+                    source,
+                    &fragment.text,
+                    fragment.origin,
+                    fragment.case,
+                );
+            }
+        }
+        trace
+    }
+}
+
+// Input text as the input stages left it
+fn text<'a>(source: &'a str, input: &'a Option<Normalized>) -> &'a str {
+    input.as_ref().map_or(source, |input| &input.text)
+}
+
+// Input split into words, transcribed with the trie if any, and the text
+// between them
+fn split(
+    input: &str,
+    found: &[(Range<usize>, String, Origin)],
+    trie: Option<Trie<'_>>,
+) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut found = found.iter().peekable();
+    let mut covered = 0;
+
+    for (offset, segment) in transcriber::segments(input) {
+        if offset < covered {
+            continue;
+        }
+
+        if let Some((source, output, origin)) =
+            found.next_if(|(source, ..)| source.start == offset)
+        {
+            pieces.push(Piece::fixed(source.clone(), output, *origin));
+            covered = source.end;
+            continue;
+        }
+
+        let span = offset..offset + segment.len();
+        // Copy non-alphabetic segment
+        if !segment.chars().next().unwrap().is_alphabetic() {
+            pieces.push(Piece {
+                span,
+                word: false,
+                fragments: Vec::new(),
+            });
+            continue;
+        }
+
+        pieces.push(Piece {
+            span,
+            word: true,
+            fragments: fragments(segment, trie),
+        });
+    }
+    pieces
+}
+
+// Fragments of the uppercased word, graphemes without a rule (or a trie)
+// copied as-is
+fn fragments(word: &str, trie: Option<Trie<'_>>) -> Vec<Fragment> {
+    let (upper, offsets) = casing::uppercase(word);
+    let mut fragments = Vec::with_capacity(upper.len());
+    let mut pos = 0;
+
+    while pos < upper.len() {
+        let (end, origin, text) = match trie
+            .and_then(|trie| trie.longest_match(&upper, pos))
+        {
+            Some((end, rule)) => {
+                (end, Origin::Rule(rule), Cow::Borrowed(rule.value))
+            }
+            None => {
+                let grapheme = upper[pos..].graphemes(true).next().unwrap();
+                let text = Cow::Owned(grapheme.to_string());
+                (pos + grapheme.len(), Origin::Unmatched, text)
+            }
+        };
+        fragments.push(Fragment {
+            source: offsets[pos]..offsets[end],
+            origin,
+            text,
+            case: None,
+        });
+        pos = end;
+    }
+    fragments
+}
+
+impl Piece {
+    // Text copied as a whole
+    fn fixed(span: Range<usize>, text: &str, origin: Origin) -> Self {
+        let fragment = Fragment {
+            source: 0..span.len(),
+            origin,
+            text: text.to_string().into(),
+            case: None,
+        };
+        Piece {
+            span,
+            word: false,
+            fragments: vec![fragment],
+        }
+    }
+}
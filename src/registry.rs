@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use strum::IntoEnumIterator;
+
+use crate::custom;
+use crate::detect::{self, Detection};
+use crate::transcriber::{TranscriberEnum, TranscriberTrait};
+
+/// Set of available transcribers: built-in languages plus rule files
+pub struct Registry {
+    transcribers: Vec<TranscriberEnum>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self {
+            transcribers: TranscriberEnum::iter().collect(),
+        }
+    }
+}
+
+impl Registry {
+    /// Adds transcriber, replacing the one with the same name or code
+    pub fn insert(&mut self, transcriber: TranscriberEnum) {
+        let position = self.transcribers.iter().position(|existing| {
+            existing.get_lang_name() == transcriber.get_lang_name()
+                || existing
+                    .get_lang_code()
+                    .eq_ignore_ascii_case(transcriber.get_lang_code())
+        });
+
+        match position {
+            Some(i) => self.transcribers[i] = transcriber,
+            None => self.transcribers.push(transcriber),
+        }
+    }
+
+    /// Loads rule file, adding or overriding its language
+    pub fn load_file(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<&'static str, custom::Error> {
+        let transcriber = custom::Transcriber::load(path)?;
+        let lang_name = transcriber.get_lang_name();
+        self.insert(transcriber.into());
+        Ok(lang_name)
+    }
+
+    /// Loads every `*.toml` rule file of directory (missing one is empty)
+    pub fn load_dir(
+        &mut self,
+        dir: impl AsRef<Path>,
+    ) -> Vec<(PathBuf, Result<&'static str, custom::Error>)> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let result = self.load_file(&path);
+                (path, result)
+            })
+            .collect()
+    }
+
+    /// Finds transcriber by language name or code (case insensitive)
+    pub fn find(&self, query: &str) -> Option<&TranscriberEnum> {
+        self.transcribers.iter().find(|transcriber| {
+            transcriber.get_lang_name() == query
+                || transcriber.get_lang_code().eq_ignore_ascii_case(query)
+        })
+    }
+
+    /// Ranks available transcribers by how typical input is for them
+    pub fn detect(&self, input: &str) -> Vec<Detection<'_>> {
+        detect::detect(self.iter(), input)
+    }
+
+    /// Iterates over available transcribers
+    pub fn iter(&self) -> impl Iterator<Item = &TranscriberEnum> {
+        self.transcribers.iter()
+    }
+
+    /// Lists names of available languages
+    pub fn lang_names(&self) -> impl Iterator<Item = &'static str> {
+        self.iter().map(|transcriber| transcriber.get_lang_name())
+    }
+}
+
+impl IntoIterator for Registry {
+    type Item = TranscriberEnum;
+    type IntoIter = std::vec::IntoIter<TranscriberEnum>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.transcribers.into_iter()
+    }
+}
@@ -0,0 +1,176 @@
+//! Reverse transcription: from Russian Cyrillic back to source spellings.
+//!
+//! Rule tables are read backwards: every rule whose output matches the
+//! Cyrillic text proposes its key. Partial spellings are ranked by cost and
+//! only the cheapest are kept, so the result is a bounded candidate list.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::casing;
+use crate::dictionary;
+use crate::normalize;
+use crate::transcriber::{self, TranscriberTrait};
+
+// Every segment costs, so longer rules are preferred...
+const SEGMENT_COST: u32 = 1;
+// ...unless they spell more letters than they produce (NGH -> НГ)
+const SILENT_COST: u32 = 2;
+// Tones and diacritics cannot be recovered from Cyrillic
+const DIACRITIC_COST: u32 = 2;
+// Cyrillic characters without any rule are copied as-is
+const UNMATCHED_COST: u32 = 10;
+// Spelling that does not transcribe back to the input
+const UNVERIFIED_COST: u32 = 20;
+
+/// Candidate source spelling (lower cost is more likely)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub text: String,
+    pub cost: u32,
+}
+
+/// Enumerates up to `limit` source spellings of Cyrillic input
+pub fn reverse<T: TranscriberTrait + ?Sized>(
+    transcriber: &T,
+    input: &str,
+    limit: usize,
+) -> Vec<Candidate> {
+    let mut candidates = vec![Candidate {
+        text: String::new(),
+        cost: 0,
+    }];
+
+    let normalized = normalize::normalize(input);
+    let input = normalized.text.as_str();
+    let exceptions = transcriber.get_exceptions();
+    let mut covered = 0;
+
+    for (offset, segment) in transcriber::segments(input) {
+        // Skip words already covered by an exception
+        if offset < covered {
+            continue;
+        }
+
+        let found = dictionary::find(exceptions, &input[offset..], |e| e.to);
+        let options = if let Some((len, entry)) = found {
+            // Exceptions name their source spelling
+            covered = offset + len;
+            let matched = &input[offset..covered];
+            vec![Candidate {
+                text: dictionary::recase(matched, entry.from),
+                cost: 0,
+            }]
+        } else if segment.chars().next().unwrap().is_alphabetic() {
+            reverse_word(transcriber, segment, limit)
+        } else {
+            vec![Candidate {
+                text: segment.to_string(),
+                cost: 0,
+            }]
+        };
+
+        // Combine with the previous words keeping the cheapest
+        candidates = candidates
+            .iter()
+            .flat_map(|prefix| {
+                options.iter().map(move |option| Candidate {
+                    text: prefix.text.clone() + &option.text,
+                    cost: prefix.cost + option.cost,
+                })
+            })
+            .collect();
+        keep_cheapest(&mut candidates, limit);
+    }
+
+    candidates
+}
+
+fn reverse_word<T: TranscriberTrait + ?Sized>(
+    transcriber: &T,
+    word: &str,
+    limit: usize,
+) -> Vec<Candidate> {
+    let upper = word.to_uppercase();
+    let width = limit.max(4) * 4;
+
+    // Cheapest partial spellings ending at every byte position
+    let mut partial: Vec<Vec<Candidate>> = vec![Vec::new(); upper.len() + 1];
+    partial[0].push(Candidate {
+        text: String::new(),
+        cost: 0,
+    });
+
+    for pos in 0..upper.len() {
+        if partial[pos].is_empty() {
+            continue;
+        }
+        keep_cheapest(&mut partial[pos], width);
+
+        let mut extensions = Vec::new();
+        for rule in transcriber.get_rules() {
+            let (body, start, end) = rule.pattern();
+            let next = pos + rule.value.len();
+            if rule.value.is_empty()
+                || body.is_empty()
+                || !upper[pos..].starts_with(rule.value)
+                || (start && pos != 0)
+                || (end && next != upper.len())
+            {
+                continue;
+            }
+
+            let silent = body
+                .chars()
+                .count()
+                .saturating_sub(rule.value.chars().count());
+            let cost = SEGMENT_COST
+                + silent as u32 * SILENT_COST
+                + if body.is_ascii() { 0 } else { DIACRITIC_COST };
+            extensions.push((next, body, cost));
+        }
+
+        // Keep characters no rule produces
+        if extensions.is_empty() {
+            let grapheme = upper[pos..].graphemes(true).next().unwrap();
+            extensions.push((pos + grapheme.len(), grapheme, UNMATCHED_COST));
+        }
+
+        let current = std::mem::take(&mut partial[pos]);
+        for (next, body, cost) in extensions {
+            for candidate in &current {
+                partial[next].push(Candidate {
+                    text: candidate.text.clone() + body,
+                    cost: candidate.cost + cost,
+                });
+            }
+        }
+        partial[pos] = current;
+    }
+
+    let mut candidates = partial.pop().unwrap_or_default();
+    keep_cheapest(&mut candidates, width);
+
+    // Prefer spellings that transcribe back to the input
+    for candidate in &mut candidates {
+        if transcriber.transcribe(&candidate.text) != upper {
+            candidate.cost += UNVERIFIED_COST;
+        }
+    }
+
+    // Restore input case
+    let case = casing::record_case(word);
+    for candidate in &mut candidates {
+        candidate.text = casing::reapply_case(&candidate.text, case);
+    }
+
+    keep_cheapest(&mut candidates, limit);
+    candidates
+}
+
+// Sorts by cost and drops duplicates and candidates beyond limit
+fn keep_cheapest(candidates: &mut Vec<Candidate>, limit: usize) {
+    candidates.sort_by(|a, b| a.cost.cmp(&b.cost).then(a.text.cmp(&b.text)));
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|candidate| seen.insert(candidate.text.clone()));
+    candidates.truncate(limit);
+}
@@ -0,0 +1,149 @@
+//! Russian spelling checks of transcriptions.
+//!
+//! Rules are written fragment by fragment, so their concatenation can
+//! break conventions no Russian word breaks: a sign starting a word or
+//! following a vowel (Ьен, Оъ), `Ы` starting a word, a consonant tripled or
+//! `ЙЙ`. Graphemes without a rule are copied as-is and leave other scripts
+//! inside Cyrillic words (Нгуyен).
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::trace::Trace;
+
+/// Spelling problem of the output
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// `Ь` or `Ъ` at the start of a word
+    SignAtStart { word: String },
+    /// `Ь` or `Ъ` after a vowel
+    SignAfterVowel { word: String },
+    /// `Ы` at the start of a word
+    YeryAtStart { word: String },
+    /// Same consonant three times in a row (runs of different consonants
+    /// are common in Russian: `Вздор`, `Мстислав`)
+    TripleConsonant { word: String },
+    /// `ЙЙ`
+    DoubleShortI { word: String },
+    /// Letter of another script copied into a Cyrillic word
+    MixedScript { word: String, copied: String },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::SignAtStart { word } => {
+                write!(f, "'{word}' starts with a sign")
+            }
+            Warning::SignAfterVowel { word } => {
+                write!(f, "'{word}' has a sign after a vowel")
+            }
+            Warning::YeryAtStart { word } => {
+                write!(f, "'{word}' starts with 'Ы'")
+            }
+            Warning::TripleConsonant { word } => {
+                write!(f, "'{word}' has a consonant tripled")
+            }
+            Warning::DoubleShortI { word } => {
+                write!(f, "'{word}' has 'ЙЙ'")
+            }
+            Warning::MixedScript { word, copied } => {
+                write!(f, "'{word}' mixes scripts, '{copied}' copied as-is")
+            }
+        }
+    }
+}
+
+/// Checks traced output, warnings in output order
+pub fn check(trace: &Trace) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    for span in words(&trace.output) {
+        let word = &trace.output[span.clone()];
+        warnings.extend(check_word(word));
+
+        // Graphemes copied without a rule into a Cyrillic word
+        if !word.chars().any(is_cyrillic) {
+            continue;
+        }
+        for segment in trace.unmatched() {
+            let copied = trace.fragment(segment);
+            if span.contains(&segment.target.start)
+                && copied.chars().any(|c| c.is_alphabetic() && !is_cyrillic(c))
+            {
+                warnings.push(Warning::MixedScript {
+                    word: word.to_string(),
+                    copied: copied.to_string(),
+                });
+            }
+        }
+    }
+    warnings
+}
+
+/// Checks transcribed text (scripts are not told apart without a trace)
+pub fn check_text(text: &str) -> Vec<Warning> {
+    words(text).flat_map(|span| check_word(&text[span])).collect()
+}
+
+// Letter runs of text (hyphenated parts are words of their own)
+fn words(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut start = None;
+    let mut chars = text.char_indices().chain([(text.len(), ' ')]);
+    std::iter::from_fn(move || {
+        for (i, c) in chars.by_ref() {
+            match (c.is_alphabetic(), start) {
+                (true, None) => start = Some(i),
+                (false, Some(first)) => {
+                    start = None;
+                    return Some(first..i);
+                }
+                _ => {}
+            }
+        }
+        None
+    })
+}
+
+fn check_word(text: &str) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let letters: Vec<char> =
+        text.chars().flat_map(char::to_uppercase).collect();
+    let word = || text.to_string();
+
+    match letters.first() {
+        Some('Ь' | 'Ъ') => {
+            warnings.push(Warning::SignAtStart { word: word() })
+        }
+        Some('Ы') => warnings.push(Warning::YeryAtStart { word: word() }),
+        _ => {}
+    }
+    if letters
+        .windows(2)
+        .any(|pair| is_vowel(pair[0]) && matches!(pair[1], 'Ь' | 'Ъ'))
+    {
+        warnings.push(Warning::SignAfterVowel { word: word() });
+    }
+    if letters.windows(3).any(|triple| {
+        is_consonant(triple[0]) && triple[1..].iter().all(|&c| c == triple[0])
+    }) {
+        warnings.push(Warning::TripleConsonant { word: word() });
+    }
+    if letters.windows(2).any(|pair| pair == ['Й', 'Й']) {
+        warnings.push(Warning::DoubleShortI { word: word() });
+    }
+    warnings
+}
+
+/// Russian letter of either case
+pub fn is_cyrillic(c: char) -> bool {
+    matches!(c, 'А'..='я' | 'Ё' | 'ё')
+}
+
+/// Russian vowel letter of either case
+pub fn is_vowel(c: char) -> bool {
+    "аеёиоуыэюяАЕЁИОУЫЭЮЯ".contains(c)
+}
+
+fn is_consonant(c: char) -> bool {
+    "БВГДЖЗЙКЛМНПРСТФХЦЧШЩ".contains(c)
+}
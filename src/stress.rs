@@ -0,0 +1,108 @@
+//! Stress marks on transcriptions.
+//!
+//! The stressed form puts a combining acute (U+0301) after the stressed
+//! vowel as declared by the language: the last syllable of a word or a
+//! hyphenated compound (Такси́н, Чан-Оча́), or every syllable of names made
+//! of monosyllables (Те́йн Се́йн). Words with `ё` or a stress mark already
+//! are left as they are, and so are user overrides.
+
+use serde::Deserialize;
+
+use crate::spelling::is_vowel;
+use crate::trace::{Origin, Trace};
+
+/// Combining acute accent
+pub const MARK: char = '\u{301}';
+
+/// Stress placement of a language
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Stress {
+    /// No stress marks
+    #[default]
+    None,
+    /// Last syllable of every word or compound (single syllables unmarked)
+    Final,
+    /// Every syllable (first vowel of every vowel group)
+    EverySyllable,
+}
+
+/// Stressed form of the traced output (override spellings kept as-is)
+pub fn stress(trace: &Trace, stress: Stress) -> String {
+    let mut output = String::with_capacity(trace.output.len() * 2);
+    let mut start = 0;
+    for segment in &trace.segments {
+        if segment.origin == Origin::Override {
+            let end = segment.target.start;
+            output += &stress_text(&trace.output[start..end], stress);
+            output += trace.fragment(segment);
+            start = segment.target.end;
+        }
+    }
+    output += &stress_text(&trace.output[start..], stress);
+    output
+}
+
+/// Stressed form of transcribed text
+pub fn stress_text(text: &str, stress: Stress) -> String {
+    if stress == Stress::None {
+        return text.to_string();
+    }
+
+    // Compounds: letters and hyphens, everything else copied
+    let is_compound = |c: char| c.is_alphabetic() || c == '-' || c == MARK;
+    let mut output = String::with_capacity(text.len() * 2);
+    let mut compound = String::new();
+    for c in text.chars() {
+        if is_compound(c) {
+            compound.push(c);
+            continue;
+        }
+        output += &stress_compound(&compound, stress);
+        compound.clear();
+        output.push(c);
+    }
+    output += &stress_compound(&compound, stress);
+    output
+}
+
+fn stress_compound(compound: &str, stress: Stress) -> String {
+    if compound.contains(['ё', 'Ё', MARK]) {
+        return compound.to_string();
+    }
+
+    // Byte offsets right after the vowels to mark
+    let vowels: Vec<_> = compound
+        .char_indices()
+        .filter(|&(_, c)| is_vowel(c))
+        .map(|(i, c)| (i, i + c.len_utf8()))
+        .collect();
+    let marks: Vec<usize> = match stress {
+        Stress::None => Vec::new(),
+        Stress::Final if vowels.len() > 1 => {
+            vowels.last().map(|&(_, end)| end).into_iter().collect()
+        }
+        Stress::Final => Vec::new(),
+        Stress::EverySyllable => {
+            let mut previous = None;
+            let mut marks = Vec::new();
+            for &(start, end) in &vowels {
+                if previous != Some(start) {
+                    marks.push(end);
+                }
+                previous = Some(end);
+            }
+            marks
+        }
+    };
+
+    let mut output = String::with_capacity(compound.len() + marks.len() * 2);
+    let mut start = 0;
+    for end in marks {
+        output += &compound[start..end];
+        output.push(MARK);
+        start = end;
+    }
+    output += &compound[start..];
+    output
+}
@@ -1,12 +1,26 @@
-use crate::transcriber::{TranscriberEnum, TranscriberTrait};
+use crate::batch::{self, Column, Language};
+use crate::casing::{self, Case};
+use crate::context;
+use crate::coverage;
+use crate::custom;
+use crate::declension;
+use crate::detect;
+use crate::name::{self, Gender, Role};
+use crate::normalize;
+use crate::orthography::{AfterVowel, Orthography};
+use crate::overrides::Overrides;
+use crate::pipeline::{self, Stage};
+use crate::registry::Registry;
+use crate::spelling;
+use crate::stress;
+use crate::trace::Origin;
+use crate::transcriber::{Rule, TranscriberEnum, TranscriberTrait};
+use crate::trie::TrieBuf;
+use crate::validate;
+use crate::variants;
+use proptest::prelude::*;
 use strum::IntoEnumIterator;
 
-#[derive(Debug)]
-pub struct Mapping<'a> {
-    pub from: &'a str,
-    pub to: &'a str,
-}
-
 #[test]
 fn test_langs() {
     for transcriber in TranscriberEnum::iter() {
@@ -17,7 +31,7 @@ fn test_langs() {
 fn test_lang<T: TranscriberTrait>(transcriber: T) {
     let mappings = transcriber.get_mappings();
     for mapping in mappings {
-        let result = transcriber.transcribe(mapping.from);
+        let (result, warnings) = transcriber.transcribe_checked(mapping.from);
         assert_eq!(
             result,
             mapping.to,
@@ -28,5 +42,1232 @@ fn test_lang<T: TranscriberTrait>(transcriber: T) {
             mapping.to,
             result,
         );
+        // Outputs follow Russian spelling
+        assert!(
+            warnings.is_empty(),
+            "'{}': '{}' -> '{}', {}",
+            transcriber.get_lang_name(),
+            mapping.from,
+            result,
+            warnings[0],
+        );
+    }
+}
+
+#[test]
+fn test_custom_langs() {
+    // Runtime transcribers must behave exactly like the compiled ones
+    for transcriber in TranscriberEnum::iter() {
+        let custom = custom::Transcriber::from_rules(
+            transcriber.get_lang_name(),
+            transcriber.get_lang_code(),
+            transcriber.get_rules(),
+            transcriber.get_post_rules(),
+            transcriber.get_exceptions(),
+            transcriber.get_markers(),
+            transcriber.get_name_convention(),
+            Vec::new(),
+        )
+        .unwrap();
+
+        for mapping in transcriber.get_mappings() {
+            assert_eq!(
+                custom.transcribe(mapping.from),
+                transcriber.transcribe(mapping.from),
+                "'{}': custom transcriber differs on '{}'",
+                transcriber.get_lang_name(),
+                mapping.from,
+            );
+        }
+    }
+}
+
+#[test]
+fn test_rule_file() {
+    const RULE_FILE: &str = r#"
+        lang_name = "Тайский"
+        lang_code = "tha"
+
+        [lang_rules]
+        "A" = "А"
+        "K" = "К"
+        "N" = "Н"
+        "S" = "С"
+        "KH" = "КХ"
+        "|S" = "Ш"
+        "N|" = "М"
+
+        [[mappings]]
+        from = "Khan Sakan"
+        to = "Кхам Шакам"
+    "#;
+
+    let transcriber = custom::Transcriber::from_toml(RULE_FILE).unwrap();
+    assert!(transcriber.check_mappings().is_empty());
+
+    // Rule file overrides built-in language with the same code
+    let mut registry = Registry::default();
+    let count = registry.iter().count();
+    registry.insert(transcriber.into());
+    assert_eq!(registry.iter().count(), count);
+    let thai = registry.find("Тайский").unwrap();
+    assert_eq!(thai.transcribe("Sanak"), "Шанак");
+
+    // Malformed rule files are reported
+    assert!(custom::Transcriber::from_toml("lang_name = 1").is_err());
+    let empty_key =
+        "lang_name = \"X\"\nlang_code = \"x\"\n[lang_rules]\n\"\" = \"Х\"";
+    assert!(matches!(
+        custom::Transcriber::from_toml(empty_key),
+        Err(custom::Error::EmptyKey)
+    ));
+    // Tables the trie cannot index are errors, not panics
+    let rules = vec![Rule::new("A", "А"); u16::MAX as usize].leak();
+    let names = TranscriberEnum::find("tha").unwrap().get_name_convention();
+    let too_large = custom::Transcriber::from_rules(
+        "X",
+        "x",
+        rules,
+        &[],
+        &[],
+        &[],
+        names,
+        Vec::new(),
+    );
+    assert!(matches!(too_large, Err(custom::Error::TooLarge)));
+
+    // Rules of the same rank apply in file order on every load
+    let contexts = |first: &str, second: &str| {
+        format!(
+            "lang_name = \"X\"\nlang_code = \"x\"\n[lang_rules]\n\"A\" = \
+             \"А\"\n\"N\" = \"Н\"\n{first}\n{second}"
+        )
+    };
+    let soft = r#""N / _[AEIOU]" = "НЬ""#;
+    let double = r#""N / [AEIOU]_" = "НН""#;
+    for _ in 0..8 {
+        let first = custom::Transcriber::from_toml(&contexts(soft, double));
+        assert_eq!(first.unwrap().transcribe("Ana"), "Аньа");
+        let first = custom::Transcriber::from_toml(&contexts(double, soft));
+        assert_eq!(first.unwrap().transcribe("Ana"), "Анна");
+    }
+
+    // Lowercase keys match like uppercase ones
+    let lowercase = r#"
+        lang_name = "X"
+        lang_code = "x"
+
+        [lang_rules]
+        "a" = "А"
+        "kh" = "Кх"
+        "n|" = "М"
+
+        [names]
+        honorifics = ["khun"]
+        genders = { "nai" = "male" }
+    "#;
+    let transcriber = custom::Transcriber::from_toml(lowercase).unwrap();
+    assert_eq!(transcriber.transcribe("Khan"), "Кхам");
+    let names = transcriber.get_name_convention();
+    assert_eq!(names.honorifics, ["KHUN"]);
+    assert_eq!(names.genders, [("NAI", Gender::Male)]);
+}
+
+#[test]
+fn test_trie() {
+    const RULES: &[Rule] = &[
+        Rule::new("N", "Н"),
+        Rule::new("NG", "НГ"),
+        Rule::new("NGH", "НГ"),
+        Rule::new("NG|", "Н"),
+        Rule::new("|NG|", "НЪ"),
+        Rule::new("E\u{301}", "Э"),
+        Rule::new("|", "-"),
+    ];
+    let table = TrieBuf::new(RULES);
+    let trie = table.trie();
+
+    // Longest match wins
+    assert_eq!(trie.longest_match("NGHE", 0).unwrap().1.value, "НГ");
+    assert_eq!(trie.longest_match("NGHE", 0).unwrap().0, 3);
+    // Anchored rules apply only at word boundaries, most specific first
+    assert_eq!(trie.longest_match("ANG", 1).unwrap().1.value, "Н");
+    assert_eq!(trie.longest_match("NG", 0).unwrap().1.value, "НЪ");
+    assert_eq!(trie.longest_match("NGA", 0).unwrap().1.value, "НГ");
+    // Keys never split graphemes, bare anchors never match
+    assert!(trie.longest_match("E\u{301}\u{302}", 0).is_none());
+    assert!(trie.longest_match("E\u{301}", 0).is_some());
+    assert!(trie.longest_match("A", 0).is_none());
+}
+
+#[test]
+fn test_context() {
+    const RULES: &[Rule] = &[
+        Rule::new("P", "П"),
+        Rule::new("P / _[AEIOU]", "ПХ"),
+        Rule::new("S", "С"),
+        Rule::new("S / [AEIOU]_|", "Т"),
+        Rule::new("N", "Н"),
+        Rule::new("K", "К"),
+        Rule::new("N / [^AEIOU] _", "НЬ"),
+        Rule::new("K / | _ H", "КХ"),
+    ];
+    let table = TrieBuf::new(RULES);
+    let trie = table.trie();
+    let value = |word, pos| trie.longest_match(word, pos).unwrap().1.value;
+
+    // Right context is checked but not consumed
+    assert_eq!(value("PA", 0), "ПХ");
+    assert_eq!(trie.longest_match("PA", 0).unwrap().0, 1);
+    assert_eq!(value("PR", 0), "П");
+    assert_eq!(value("P", 0), "П");
+    // Left context and word boundaries
+    assert_eq!(value("AS", 1), "Т");
+    assert_eq!(value("ASA", 1), "С");
+    assert_eq!(value("RS", 1), "С");
+    assert_eq!(value("RN", 1), "НЬ");
+    assert_eq!(value("AN", 1), "Н");
+    assert_eq!(value("N", 0), "Н");
+    assert_eq!(value("KH", 0), "КХ");
+    assert_eq!(value("AKH", 1), "К");
+
+    assert_eq!(context::split("P / _[AEIOU]"), ("P", Some(" _[AEIOU]")));
+    assert_eq!(context::split("|P"), ("|P", None));
+
+    // Thai P/T before vowels read as the whole-syllable rules they replace
+    let thai = TranscriberEnum::find("tha").unwrap();
+    for (from, to) in [
+        ("Pattaya", "Пхаттхаья"),
+        ("Paya", "Пхаья"),
+        ("Tiya", "Тхиья"),
+        ("Phaya", "Пхая"),
+        ("Soya", "Соя"),
+    ] {
+        assert_eq!(thai.transcribe(from), to, "{from}");
+    }
+}
+
+#[test]
+fn test_trace() {
+    let transcriber = TranscriberEnum::find("vie").unwrap();
+    let trace = transcriber.trace("Lê Khả-Phiêu!");
+    assert_eq!(trace.output, "Ле Кха-Фьеу!");
+
+    let rows: Vec<_> = trace
+        .segments
+        .iter()
+        .map(|segment| {
+            (
+                segment.source.clone(),
+                segment.rule_key(),
+                trace.fragment(segment),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (0..1, Some("L"), "Л"),
+            (1..3, Some("Ê"), "е"),
+            (3..4, None, " "),
+            (4..5, Some("K"), "К"),
+            (5..6, Some("H"), "х"),
+            (6..9, Some("Ả"), "а"),
+            (9..10, None, "-"),
+            (10..12, Some("PH"), "Ф"),
+            (12..16, Some("IÊU"), "ьеу"),
+            (16..17, None, "!"),
+        ]
+    );
+
+    // Pass-through graphemes are reported separately
+    let trace = transcriber.trace("Lê 李");
+    let unmatched: Vec<_> = trace.unmatched().collect();
+    assert_eq!(unmatched.len(), 1);
+    assert_eq!(unmatched[0].source, 4..7);
+    assert_eq!(trace.fragment(unmatched[0]), "李");
+}
+
+#[test]
+fn test_trace_spans() {
+    for transcriber in TranscriberEnum::iter() {
+        for mapping in transcriber.get_mappings() {
+            let trace = transcriber.trace(mapping.from);
+
+            // Segments tile both input and output without gaps
+            let mut source_end = 0;
+            let mut target_end = 0;
+            for segment in &trace.segments {
+                assert_eq!(segment.source.start, source_end);
+                assert_eq!(segment.target.start, target_end);
+                source_end = segment.source.end;
+                target_end = segment.target.end;
+            }
+            assert_eq!(source_end, mapping.from.len());
+            assert_eq!(target_end, trace.output.len());
+        }
+    }
+}
+
+#[test]
+fn test_reverse() {
+    let burmese = TranscriberEnum::find("bur").unwrap();
+    let candidates = burmese.reverse("Тхин Чжо", 5);
+    assert_eq!(candidates.len(), 5);
+    assert_eq!(candidates[0].text, "Htin Kyaw");
+    assert!(candidates.windows(2).all(|pair| pair[0].cost <= pair[1].cost));
+
+    let vietnamese = TranscriberEnum::find("vie").unwrap();
+    let candidates = vietnamese.reverse("НГУЕН-ван линь", 20);
+    assert!(candidates.iter().any(|c| c.text == "NGUEN-van linh"));
+
+    // The most likely spelling transcribes back to the input
+    for transcriber in TranscriberEnum::iter() {
+        for mapping in transcriber.get_mappings() {
+            let candidates = transcriber.reverse(mapping.to, 3);
+            assert_eq!(
+                transcriber.transcribe(&candidates[0].text),
+                mapping.to,
+                "'{}': '{}' reversed to '{}'",
+                transcriber.get_lang_name(),
+                mapping.to,
+                candidates[0].text,
+            );
+        }
+    }
+}
+
+#[test]
+fn test_variants() {
+    let vietnamese = TranscriberEnum::find("vie").unwrap();
+    let variants = vietnamese.transcribe_variants("Hồ Chí Minh", 10);
+    let texts: Vec<_> = variants.iter().map(|v| v.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        ["Хо Ти Минь", "Хо Ши Минь", "Хо Ти Мин", "Хо Ши Мин"]
+    );
+    let ranks: Vec<_> = variants.iter().map(|v| v.rank).collect();
+    assert_eq!(ranks, [0, 1, 1, 2]);
+
+    // Limit keeps the best ranked readings
+    let variants = vietnamese.transcribe_variants("HỒ CHÍ MINH", 2);
+    assert_eq!(variants[0].text, "ХО ТИ МИНЬ");
+    assert_eq!(variants[1].text, "ХО ШИ МИНЬ");
+
+    // Burmese `AYE` reads `Э` or `Е`
+    let burmese = TranscriberEnum::find("bur").unwrap();
+    let variants = burmese.transcribe_variants("Aye Ko", 10);
+    assert_eq!(variants.len(), 2);
+    assert_eq!(variants[1].text, "Е Ко");
+
+    // Single reading without alternatives
+    assert_eq!(burmese.transcribe_variants("Ne Win", 10).len(), 1);
+
+    // Rule files list readings as arrays
+    let source = "lang_name = \"X\"\nlang_code = \"x\"\n\
+        [lang_rules]\n\"A\" = [\"А\", \"Я\"]\n\"B\" = \"Б\"";
+    let custom = custom::Transcriber::from_toml(source).unwrap();
+    let variants = custom.transcribe_variants("Ba", 10);
+    assert_eq!(variants[1].text, "Бя");
+
+    // Alternatives go through post rules and spelling conventions too
+    let source = "lang_name = \"X\"\nlang_code = \"x\"\n\
+        [lang_rules]\n\"A\" = [\"А\", \"Ё\"]\n\"N\" = \"НЬ\"\n\
+        [post_rules]\n\"ЬЁ\" = \"Ё\"";
+    let custom = custom::Transcriber::from_toml(source).unwrap();
+    let texts = |orthography: &Orthography| {
+        let variants = variants::variants(
+            &custom,
+            "NA",
+            &Overrides::default(),
+            orthography,
+            10,
+        );
+        variants.into_iter().map(|v| v.text).collect::<Vec<_>>()
+    };
+    assert_eq!(texts(&Orthography::default()), ["НЬА", "НЁ"]);
+    let folded = Orthography {
+        fold_yo: true,
+        ..Orthography::default()
+    };
+    assert_eq!(texts(&folded), ["НЬА", "НЕ"]);
+}
+
+#[test]
+fn test_exceptions() {
+    let thai = TranscriberEnum::find("tha").unwrap();
+    assert_eq!(thai.transcribe("THAKSIN"), "ТАКСИН");
+    assert_eq!(thai.transcribe("prayut chan-o-cha"), "прают чан-оча");
+    // Exceptions never leak into longer words
+    assert_eq!(thai.transcribe("Thaksina"), "Тхаксина");
+    // ...nor into letters carrying combining marks of any block
+    let trace = thai.trace("Thaksin\u{1DC0}");
+    assert!(!matches!(trace.segments[0].origin, Origin::Exception(_)));
+
+    let trace = thai.trace("Thaksin Shinawatra");
+    assert_eq!(trace.segments[0].source, 0..7);
+    assert!(matches!(trace.segments[0].origin, Origin::Exception(_)));
+
+    // Reverse transcription proposes the traditional spelling
+    let burmese = TranscriberEnum::find("bur").unwrap();
+    assert_eq!(burmese.reverse("Ба Све", 3)[0].text, "Ba Swe");
+
+    // Diacritics distinguish exceptions
+    let source = "lang_name = \"X\"\nlang_code = \"x\"\n\
+        [lang_rules]\n\"A\" = \"А\"\n\"E\" = \"Е\"\n\
+        [exceptions]\n\"Ê\" = \"Э\"";
+    let custom = custom::Transcriber::from_toml(source).unwrap();
+    assert_eq!(custom.transcribe("ê e"), "э е");
+}
+
+#[test]
+fn test_overrides() {
+    let vietnamese = TranscriberEnum::find("vie").unwrap();
+    let mut overrides = Overrides::default();
+    overrides.insert("VIE", "Nguyễn Văn Linh", "Нгуен Ван Лин");
+    overrides.insert("tha", "Linh", "Линх");
+
+    // Overrides apply to their language only and are reported
+    let trace = vietnamese.trace_with("Nguyễn Văn Linh, Lê Linh", &overrides);
+    assert_eq!(trace.output, "Нгуен Ван Лин, Ле Линь");
+    assert!(trace.overridden());
+    assert!(!vietnamese.trace("Nguyễn Văn Linh").overridden());
+
+    // Overrides take precedence over exceptions
+    let thai = TranscriberEnum::find("tha").unwrap();
+    overrides.insert("tha", "Thaksin", "Тхаксин");
+    assert_eq!(thai.trace_with("THAKSIN", &overrides).output, "ТХАКСИН");
+
+    // Overrides survive a round trip through the file format
+    let source = overrides.to_toml().unwrap();
+    assert_eq!(Overrides::from_toml(&source).unwrap(), overrides);
+    assert_eq!(overrides.remove("tha", "Linh").as_deref(), Some("Линх"));
+    assert_eq!(
+        overrides.get("vie", "Nguyễn Văn Linh"),
+        Some("Нгуен Ван Лин")
+    );
+
+    // Names are stored normalized
+    overrides.insert("vie", "Le\u{302}", "Лэ");
+    assert_eq!(vietnamese.trace_with("Lê", &overrides).output, "Лэ");
+}
+
+#[test]
+fn test_batch() {
+    let registry = Registry::default();
+    let overrides = Overrides::default();
+    let mut options = batch::Options {
+        columns: vec![Column::from("Name")],
+        language: Language::Column(Column::from("lang")),
+        delimiter: ',',
+        header: true,
+        orthography: Orthography::default(),
+    };
+
+    // Other fields keep their quoting and line endings
+    let input = "id,Name,lang\r\n\
+        1,\"Tô Lâm\",vie\r\n\
+        2,\"Thaksin, Shinawatra\",tha\n\
+        3,\"Ba \"\"Swe\"\"\",bur\n\
+        4,Ne Win,xx";
+    let result =
+        batch::transcribe(&registry, &overrides, &options, input).unwrap();
+    assert_eq!(
+        result.output,
+        "id,Name,lang,Name_ru\r\n\
+        1,\"Tô Lâm\",vie,То Лам\r\n\
+        2,\"Thaksin, Shinawatra\",tha,\"Таксин, Чинават\"\n\
+        3,\"Ba \"\"Swe\"\"\",bur,\"Ба \"\"Шве\"\"\"\n\
+        4,Ne Win,xx,"
+    );
+    assert_eq!(result.skipped, [(5, "xx".to_string())]);
+
+    // Columns by number, short rows stay aligned
+    options.columns = vec![Column::from("1")];
+    options.language = Language::Fixed("bur".into());
+    options.delimiter = '\t';
+    options.header = false;
+    let input = "Ne Win\tx\nSan Yu\n";
+    let result =
+        batch::transcribe(&registry, &overrides, &options, input).unwrap();
+    assert_eq!(result.output, "Ne Win\tx\tНе Вин\nSan Yu\t\tСан Ю\n");
+
+    // Language detected row by row
+    options.language = Language::Auto;
+    let input = "Tô Lâm\nNe Win\nAnna\n";
+    let result =
+        batch::transcribe(&registry, &overrides, &options, input).unwrap();
+    assert_eq!(result.output, "Tô Lâm\tТо Лам\nNe Win\tНе Вин\nAnna\t\n");
+    assert_eq!(result.skipped, [(3, String::new())]);
+
+    // Trailing delimiter at the end of input keeps the last row
+    options.language = Language::Fixed("bur".into());
+    options.delimiter = ',';
+    let result =
+        batch::transcribe(&registry, &overrides, &options, "Ne Win,").unwrap();
+    assert_eq!(result.output, "Ne Win,,Не Вин");
+
+    // Spelling conventions as the pipeline applies them
+    options.language = Language::Fixed("vie".into());
+    options.orthography.after_vowel = AfterVowel::Full;
+    let result =
+        batch::transcribe(&registry, &overrides, &options, "Hoài").unwrap();
+    assert_eq!(result.output, "Hoài,Хоаи");
+    options.orthography = Orthography::default();
+    options.language = Language::Auto;
+    options.delimiter = '\t';
+
+    // Malformed tables and unknown columns are errors
+    assert!(matches!(
+        batch::transcribe(&registry, &overrides, &options, "\"Ne Win"),
+        Err(batch::Error::UnclosedQuote { line: 1 })
+    ));
+    options.columns = vec![Column::from("Name")];
+    assert!(matches!(
+        batch::transcribe(&registry, &overrides, &options, "Ne Win"),
+        Err(batch::Error::UnknownColumn(_))
+    ));
+}
+
+#[test]
+fn test_normalize() {
+    let vietnamese = TranscriberEnum::find("vie").unwrap();
+
+    // Decomposed diacritics match precomposed keys
+    let nfd = "Nguye\u{302}\u{303}n Ta\u{302}n";
+    assert_eq!(vietnamese.transcribe(nfd), "Нгуен Тан");
+    assert_eq!(
+        vietnamese.transcribe("Ho\u{302}\u{300} Chi\u{301} Minh"),
+        "Хо Ти Минь"
+    );
+
+    // Invisible characters are dropped, apostrophes unified
+    let normalized =
+        normalize::normalize("\u{200B}Lê\u{202C} O\u{2019}Lâm\u{FEFF}");
+    assert_eq!(normalized.text, "Lê O'Lâm");
+    // Modifier letters (Hawaiian ʻokina, Uzbek ʼ) are not apostrophes
+    assert!(normalize::is_normalized("Lê O'Lâm"));
+    assert!(!normalize::is_normalized("O\u{2019}Le\u{302}"));
+    let normalized = normalize::normalize("Oʻahu Maʼruf");
+    assert_eq!(normalized.text, "Oʻahu Maʼruf");
+
+    // Trace spans lead back to the original input
+    let input = "\u{200B}Ta\u{302}n \u{2066}Lâm\u{2069}";
+    let trace = vietnamese.trace(input);
+    assert_eq!(trace.output, "Тан Лам");
+    let sources: Vec<_> = trace
+        .segments
+        .iter()
+        .map(|segment| &input[segment.source.clone()])
+        .collect();
+    assert_eq!(
+        sources,
+        [
+            "\u{200B}T",
+            "a\u{302}",
+            "n",
+            " ",
+            "\u{2066}L",
+            "â",
+            "m\u{2069}"
+        ]
+    );
+
+    // Reverse transcription normalizes Cyrillic too
+    let burmese = TranscriberEnum::find("bur").unwrap();
+    let candidates = burmese.reverse("Маун Аи\u{306}", 1);
+    assert_eq!(candidates[0].text, burmese.reverse("Маун Ай", 1)[0].text);
+}
+
+#[test]
+fn test_detect() {
+    let registry = Registry::default();
+
+    // Every example name is recognized as its own language
+    for transcriber in TranscriberEnum::iter() {
+        for mapping in transcriber.get_mappings() {
+            let detections = registry.detect(mapping.from);
+            assert_eq!(
+                detections[0].transcriber.get_lang_code(),
+                transcriber.get_lang_code(),
+                "'{}' detected as '{}'",
+                mapping.from,
+                detections[0].transcriber.get_lang_name(),
+            );
+        }
+    }
+
+    // Confidence is the share of the best score
+    let detections = registry.detect("Nguyen Van Linh");
+    assert_eq!(detections[0].transcriber.get_lang_code(), "vie");
+    assert!(detections[0].confidence > 0.5);
+    let total: f32 = detections.iter().map(|d| d.confidence).sum();
+    assert!((total - 1.0).abs() < 1e-6);
+
+    // Nothing typical, nothing confident
+    let detections = registry.detect("Anna");
+    assert!(detections.iter().all(|d| d.confidence == 0.0));
+    // Penalties of other languages are no evidence
+    for name in ["Quinn", "Jack"] {
+        let detections = registry.detect(name);
+        assert!(detections.iter().all(|d| d.confidence == 0.0), "{name}");
+    }
+    // Accented family names are markers too
+    for name in ["Trần", "Lê", "Đỗ"] {
+        let detections = registry.detect(name);
+        assert_eq!(detections[0].transcriber.get_lang_code(), "vie");
+        assert!(detections[0].confidence > 0.0, "{name}");
+    }
+    // Rule file markers match whatever their case
+    let rule_file = |marker: &str| {
+        let source = format!(
+            "lang_name = \"X\"\nlang_code = \"x\"\nmarkers = \
+             [\"{marker}\"]\n[lang_rules]\n\"SAWAT\" = \"Сават\""
+        );
+        custom::Transcriber::from_toml(&source).unwrap()
+    };
+    let score = detect::score(&rule_file("sawat"), "Sawat");
+    assert!(score > 0);
+    assert_eq!(score, detect::score(&rule_file("SAWAT"), "Sawat"));
+}
+
+#[test]
+fn test_validate() {
+    // Built-in tables are clean
+    for transcriber in TranscriberEnum::iter() {
+        let report = validate::report(&transcriber);
+        assert!(report.is_empty(), "{report}");
+    }
+
+    const RULES: &[Rule] = &[
+        Rule::new("K", "К"),
+        Rule::new("K", "Г"),
+        Rule::new("P / _[AEIOU]", "ПХ"),
+        Rule::new("P/_[AEIOU]", "ПХ"),
+        Rule::new("|", "Ъ"),
+        Rule::new("kh", "Х"),
+        Rule::new("E\u{301}", "Э"),
+        Rule::new("A|B", "АБ"),
+        Rule::new("S / _[AEIOU", "С"),
+        Rule::new("X", "KS"),
+        Rule::new("AB", "АБ"),
+        Rule::new("A B", "АБ"),
+        Rule::new("A-B", "АБ"),
+        Rule::new("O'L", "ОЛ"),
+    ];
+    let issues = validate::validate(RULES);
+    let issue =
+        |key| issues.iter().find(|issue| format!("{issue}").contains(key));
+    assert!(matches!(
+        issues[0],
+        validate::Issue::Conflict {
+            value: "Г",
+            earlier: "К",
+            ..
+        }
+    ));
+    assert!(matches!(
+        issue("'P/_[AEIOU]'"),
+        Some(validate::Issue::Shadowed { .. })
+    ));
+    assert!(matches!(issue("'|'"), Some(validate::Issue::Empty { .. })));
+    assert!(matches!(
+        issue("'kh'"),
+        Some(validate::Issue::Lowercase { .. })
+    ));
+    assert!(matches!(
+        issue("'E\u{301}'"),
+        Some(validate::Issue::NotNfc { .. })
+    ));
+    assert!(matches!(
+        issue("'A|B'"),
+        Some(validate::Issue::InnerAnchor { .. })
+    ));
+    assert!(matches!(
+        issue("'S /"),
+        Some(validate::Issue::MalformedContext { .. })
+    ));
+    assert!(matches!(
+        issue("'X'"),
+        Some(validate::Issue::NonCyrillic { .. })
+    ));
+    // Words hold only letters, and spacing inside them is no respacing
+    for key in ["'A B'", "'A-B'", "'O'L'"] {
+        assert!(matches!(
+            issue(key),
+            Some(validate::Issue::NotAlphabetic { .. })
+        ));
+    }
+    assert_eq!(issues.len(), 11);
+
+    // Post rules are checked against the uppercased output
+    const POST_RULES: &[Rule] = &[Rule::new("ЙЙ", "Й"), Rule::new("ьь", "Ь")];
+    let names = TranscriberEnum::find("tha").unwrap().get_name_convention();
+    let custom = custom::Transcriber::from_rules(
+        "X",
+        "x",
+        &RULES[..1],
+        POST_RULES,
+        &[],
+        &[],
+        names,
+        Vec::new(),
+    )
+    .unwrap();
+    assert_eq!(
+        validate::report(&custom),
+        "X (x): 1 issue(s)\n  \
+        post rules: 'ьь' has lowercase letters and never matches\n"
+    );
+}
+
+#[test]
+fn test_coverage() {
+    const RULE_FILE: &str = r#"
+        lang_name = "Тайский"
+        lang_code = "tha"
+
+        [lang_rules]
+        "N" = "Н"
+
+        [lang_rules.Vowels]
+        "A" = "А"
+        "O" = "О"
+
+        [lang_rules.Consonants]
+        "K" = "К"
+        "KH" = "КХ"
+
+        [post_rules]
+        "КХА" = "КХА"
+        "ЬЬ" = "Ь"
+
+        [exceptions]
+        "Thaksin" = "Таксин"
+        "Prayut" = "Прают"
+
+        [[mappings]]
+        from = "Khan Thaksin"
+        to = "Кхан Таксин"
+    "#;
+
+    let transcriber = custom::Transcriber::from_toml(RULE_FILE).unwrap();
+    let result = coverage::coverage(&transcriber);
+    let mut unused: Vec<_> = result.unused.iter().map(|r| r.key).collect();
+    unused.sort();
+    assert_eq!(unused, ["K", "O"]);
+    assert_eq!(result.unused_exceptions[0].from, "Prayut");
+    assert_eq!(result.ratio(), 0.6);
+    assert_eq!(result.post_rules, 2);
+    let unused: Vec<_> =
+        result.unused_post_rules.iter().map(|r| r.key).collect();
+    assert_eq!(unused, ["ЬЬ"]);
+
+    let report = coverage::report(&transcriber);
+    assert!(report.starts_with("Тайский (tha): 3/5 rules (60%)\n"));
+    assert!(report.contains("  Vowels: 1/2, unused: 'O'\n"));
+    assert!(report.contains("  Consonants: 1/2, unused: 'K'\n"));
+    assert!(report.contains("  (no section): 1/1\n"));
+    assert!(report.contains("  post rules: 1/2, unused: 'ЬЬ'\n"));
+    assert!(report.contains("  exceptions: 1/2, unused: 'Prayut'\n"));
+
+    // Sections of built-in tables are kept
+    let thai = TranscriberEnum::find("tha").unwrap();
+    assert!(thai.get_rules().iter().all(|rule| !rule.section.is_empty()));
+}
+
+#[test]
+fn test_names() {
+    let overrides = Overrides::default();
+    let parse = |lang, input| {
+        let transcriber = TranscriberEnum::find(lang).unwrap();
+        name::parse(&transcriber, input, &overrides, &Orthography::default())
+    };
+
+    // Surname first, middle name kept with the given one
+    let vie = parse("vie", "Nguyễn Văn Linh");
+    let roles: Vec<_> = vie.parts.iter().map(|part| part.role).collect();
+    assert_eq!(roles, [Role::Family, Role::Middle, Role::Given]);
+    assert_eq!(vie.family(), Some("Нгуен"));
+    assert_eq!(vie.given(), "Ван Линь");
+    assert_eq!(vie.format(), "Нгуен Ван Линь");
+
+    // Surname last, whole-name exception split into parts
+    let tha = parse("tha", "Khun Prayut Chan-o-cha");
+    assert_eq!(tha.parts[0].role, Role::Honorific);
+    assert_eq!(tha.honorifics(), "Кхун");
+    assert_eq!(tha.family(), Some("Чан-Оча"));
+    assert_eq!(tha.format(), "Чан-Оча Прают");
+
+    // No surname, honorifics dropped
+    let bur = parse("bur", "U Thein Sein");
+    assert_eq!(bur.family(), None);
+    assert_eq!(bur.format(), "Тейн Сейн");
+    // Lone honorific-like part is a name
+    assert_eq!(parse("bur", "U").parts[0].role, Role::Given);
+}
+
+#[test]
+fn test_declension() {
+    use declension::Case::*;
+    use name::Gender::*;
+
+    // Consonant-final and soft male names decline, middle names stay
+    let vie = TranscriberEnum::find("vie").unwrap();
+    let name = name::parse(
+        &vie,
+        "Nguyễn Văn Linh",
+        &Overrides::default(),
+        &Orthography::default(),
+    );
+    let dative = declension::decline_name(&name, Male, Dative);
+    assert_eq!(dative.format(), "Нгуену Ван Линю");
+    // Edited output declines in the roles of the parts, middle names stay
+    // when its words no longer line up with them
+    for (edited, declined) in [
+        ("Нгуен Ван Линь", "Нгуену Ван Линю"),
+        ("Нгуен Ван Тхань Линь", "Нгуену Ван Тхань Линю"),
+        ("Нгуен Линь", "Нгуену Линю"),
+    ] {
+        let form = declension::decline_edited(&name, edited, Male, Dative);
+        assert_eq!(form, declined);
+    }
+
+    let forms: Vec<_> = declension::paradigm("Линь", Male)
+        .into_iter()
+        .map(|(_, form)| form)
+        .collect();
+    assert_eq!(forms, ["Линь", "Линя", "Линю", "Линя", "Линем", "Лине"]);
+    assert_eq!(declension::decline("Тхай", Male, Genitive), "Тхая");
+    assert_eq!(declension::decline("Ичий", Male, Prepositional), "Ичии");
+    assert_eq!(declension::decline("Тхач", Male, Instrumental), "Тхачем");
+    assert_eq!(declension::decline("НГУЕН", Male, Genitive), "НГУЕНА");
+
+    // Vowel-final and female consonant-final names are indeclinable
+    assert_eq!(declension::decline("Тхи Ле", Male, Dative), "Тхи Ле");
+    assert_eq!(declension::decline("Хыонг", Female, Dative), "Хыонг");
+    assert_eq!(declension::decline("Линь", Female, Instrumental), "Линью");
+    // Only the last part of a hyphenated name
+    assert_eq!(declension::decline("Чан-Тхан", Male, Dative), "Чан-Тхану");
+}
+
+#[test]
+fn test_gender() {
+    let infer = |lang, input| {
+        let transcriber = TranscriberEnum::find(lang).unwrap();
+        name::infer_gender(&transcriber, input)
+    };
+
+    let vie = infer("vie", "Nguyễn Thị Kim Ngân");
+    assert_eq!(vie.gender, Some(Gender::Female));
+    assert_eq!(vie.evidence, [("Thị".to_string(), Gender::Female)]);
+    assert_eq!(infer("vie", "NGUYEN VAN LINH").gender, Some(Gender::Male));
+    assert_eq!(infer("bur", "Daw Khin Kyi").gender, Some(Gender::Female));
+    assert_eq!(
+        infer("tha", "Nangsao Yingluck").gender,
+        Some(Gender::Female)
+    );
+
+    // No or conflicting evidence
+    assert_eq!(infer("tha", "Thaksin Shinawatra").gender, None);
+    let conflict = infer("bur", "U Ma");
+    assert_eq!(conflict.gender, None);
+    assert_eq!(conflict.evidence.len(), 2);
+}
+
+#[test]
+fn test_stress() {
+    use stress::Stress::*;
+
+    // Last syllable of words and compounds, monosyllables unmarked
+    let tha = TranscriberEnum::find("tha").unwrap();
+    let stressed = tha.transcribe_stressed("Prayut Chan-o-cha");
+    assert_eq!(stressed, "Праю\u{301}т Чан-Оча\u{301}");
+    assert_eq!(stress::stress_text("Ван Линь", Final), "Ван Линь");
+    // Every syllable of Burmese names
+    let bur = TranscriberEnum::find("bur").unwrap();
+    assert_eq!(bur.transcribe_stressed("Aung"), "А\u{301}ун");
+    assert_eq!(
+        stress::stress_text("Нейпидо", EverySyllable),
+        "Не\u{301}йпи\u{301}до\u{301}"
+    );
+    // Ё and marks already present are stress enough
+    assert_eq!(stress::stress_text("Зёнг", Final), "Зёнг");
+    assert_eq!(stress::stress_text("Та\u{301}ксин", Final), "Та\u{301}ксин");
+    assert_eq!(stress::stress_text("Таксин", None), "Таксин");
+
+    // Stress marks come off to the plain form, overrides are kept
+    let plain = tha.transcribe("Prayut Chan-o-cha");
+    assert_eq!(stressed.replace('\u{301}', ""), plain);
+    let mut overrides = Overrides::default();
+    overrides.insert("tha", "Thaksin", "Тхаксин");
+    let trace = tha.trace_with("Thaksin Prayut", &overrides);
+    assert_eq!(stress::stress(&trace, Final), "Тхаксин Праю\u{301}т");
+}
+
+#[test]
+fn test_orthography() {
+    let vie = TranscriberEnum::find("vie").unwrap();
+    let default = Orthography::default();
+    assert_eq!(
+        vie.transcribe_in("Nguyễn Văn Linh", &default),
+        "Нгуен Ван Линь"
+    );
+
+    // Ё folding and final soft sign, rule tables untouched
+    let documents = Orthography {
+        fold_yo: true,
+        drop_final_soft_sign: true,
+        ..Orthography::default()
+    };
+    assert_eq!(documents.apply("Зёнг НЬЁ Линь"), "Зенг НЬЕ Лин");
+    assert!(vie.get_rules().iter().any(|rule| rule.value == "ЗЁ"));
+
+    // Й/И after vowels (stress marks do not get in the way)
+    let full = Orthography {
+        after_vowel: AfterVowel::Full,
+        ..Orthography::default()
+    };
+    assert_eq!(full.apply("Хоай Тха\u{301}й Йен"), "Хоаи Тха\u{301}и Йен");
+    let short = Orthography {
+        after_vowel: AfterVowel::Short,
+        ..Orthography::default()
+    };
+    assert_eq!(short.apply("Хоаи Ин"), "Хоай Ин");
+
+    // Forbidden sequences in any case
+    let replaced = Orthography {
+        replacements: vec![("ьё".into(), "ё".into())],
+        ..Orthography::default()
+    };
+    assert_eq!(replaced.apply("Льёнг ЛЬЁНГ"), "Лёнг ЛЁНГ");
+}
+
+#[test]
+fn test_fragment_case() {
+    const RULE_FILE: &str = r#"
+        lang_name = "Тестовый"
+        lang_code = "tst"
+
+        [lang_rules]
+        "MC" = "МАК"
+        "EA" = "И"
+        "H" = ""
+        "A" = "А"
+        "D" = "Д"
+        "I" = "И"
+        "L" = "Л"
+        "N" = "Н"
+        "O" = "О"
+        "P" = "П"
+    "#;
+
+    let transcriber = custom::Transcriber::from_toml(RULE_FILE).unwrap();
+    // Internal capitals survive, a lone capital among capitals is upper
+    assert_eq!(transcriber.transcribe("McLean"), "МакЛин");
+    assert_eq!(transcriber.transcribe("DiNapoli"), "ДиНаполи");
+    assert_eq!(transcriber.transcribe("McLEAN"), "МакЛИН");
+    assert_eq!(transcriber.transcribe("MCLEAN"), "МАКЛИН");
+    assert_eq!(transcriber.transcribe("Mclean"), "Маклин");
+    // Capital of a silent letter passes to the next fragment
+    assert_eq!(transcriber.transcribe("Hanna"), "Анна");
+    assert_eq!(transcriber.transcribe("DiHanna"), "ДиАнна");
+
+    assert_eq!(casing::record_fragment_case("JOHN", 0..1), Case::Upper);
+    assert_eq!(casing::record_fragment_case("John", 0..1), Case::Title);
+    assert_eq!(casing::record_fragment_case("John", 1..4), Case::Lower);
+}
+
+#[test]
+fn test_post_rules() {
+    const RULE_FILE: &str = r#"
+        lang_name = "Тестовый"
+        lang_code = "tst"
+
+        [lang_rules]
+        "IE" = "ЬЕ"
+        "AI" = "АЙ"
+        "Y" = "Й"
+        "L" = "Л"
+        "N" = "Н"
+
+        [post_rules]
+        "|ЬЕ" = "ИЕ"
+        "йй" = "й"
+    "#;
+
+    let transcriber = custom::Transcriber::from_toml(RULE_FILE).unwrap();
+    assert_eq!(transcriber.get_post_rules().len(), 2);
+    // Anchored at the word start only
+    assert_eq!(transcriber.transcribe("Ien Lien"), "Иен Льен");
+    assert_eq!(transcriber.transcribe("IEN"), "ИЕН");
+    // Across fragments, in the case of the source
+    assert_eq!(transcriber.transcribe("Aiy"), "Ай");
+    assert_eq!(transcriber.transcribe("AIY"), "АЙ");
+    // Unmatched text is left as it is
+    assert_eq!(transcriber.transcribe("Йй Ьен"), "Йй Ьен");
+
+    let vietnamese = TranscriberEnum::find("vie").unwrap();
+    assert_eq!(vietnamese.transcribe("Iên Nhiên"), "Иен Ниен");
+    let trace = vietnamese.trace("Iên");
+    assert_eq!(trace.fragment(&trace.segments[0]), "Ие");
+}
+
+// Language reading Greek letters through an input stage
+mod greek {
+    use crate::normalize::{self, Normalized};
+    use crate::transcriber::Mapping;
+
+    fn romanize(input: &str) -> Normalized {
+        normalize::map(input, |grapheme| match grapheme {
+            "α" => "a".to_string(),
+            "Α" => "A".to_string(),
+            "β" => "b".to_string(),
+            "ψ" => "ps".to_string(),
+            grapheme => grapheme.to_string(),
+        })
+    }
+
+    crate::create_transcriber!(
+        lang_name: "Греческий",
+        lang_code: "grc",
+        lang_rules: {
+            "LETTERS" { "A" => "А", "B" => "Б", "PS" => "ПС" },
+        },
+        names: {
+            order: FamilyLast,
+            honorifics: {},
+            genders: {},
+            stress: None,
+        },
+        stages: { Normalize, Input(romanize), Rules, Casing },
+        mappings: { vec![Mapping { from: "Αβα", to: "Аба" }] }
+    );
+}
+
+#[test]
+fn test_pipeline() {
+    let vietnamese = TranscriberEnum::find("vie").unwrap();
+    assert_eq!(vietnamese.get_stages().len(), pipeline::DEFAULT.len());
+
+    // Input stage: spans lead back to the Greek letters
+    let greek = greek::Transcriber;
+    assert_eq!(greek.transcribe("Αβα ψα"), "Аба пса");
+    let trace = greek.trace("ψα");
+    assert_eq!(trace.segments[0].source, 0..2);
+    assert_eq!(trace.fragment(&trace.segments[0]), "пс");
+    for mapping in greek.get_mappings() {
+        assert_eq!(greek.transcribe(mapping.from), mapping.to);
+    }
+
+    const RULE_FILE: &str = r#"
+        lang_name = "Тестовый"
+        lang_code = "tst"
+        stages = ["normalize", "rules", "post-rules"]
+
+        [lang_rules]
+        "A" = "А"
+        "I" = "Й"
+
+        [post_rules]
+        "ЙЙ" = "Й"
+
+        [exceptions]
+        "Ia" = "Я"
+    "#;
+
+    // No exceptions, output stays uppercased without casing
+    let custom = custom::Transcriber::from_toml(RULE_FILE).unwrap();
+    assert_eq!(custom.transcribe("Ia Aii"), "ЙА АЙ");
+    let stages = &[Stage::Exceptions, Stage::Rules];
+    let custom = custom.with_stages(stages).unwrap();
+    assert_eq!(custom.transcribe("Ia Aii"), "Я АЙЙ");
+    // Orthography is a stage before casing
+    let orthography = Orthography {
+        after_vowel: AfterVowel::Full,
+        ..Orthography::default()
+    };
+    assert_eq!(vietnamese.transcribe_in("Hoài", &orthography), "Хоаи");
+    // Overrides and spelling conventions in one trace
+    let overrides = Overrides::default();
+    let trace = vietnamese.trace_in("Hoài", &overrides, &orthography);
+    assert_eq!(trace.output, "Хоаи");
+    assert_eq!(trace.fragment(&trace.segments[2]), "аи");
+
+    // Input stages after word stages are rejected
+    let unordered = RULE_FILE.replace(
+        r#"["normalize", "rules", "post-rules"]"#,
+        r#"["rules", "normalize"]"#,
+    );
+    assert!(matches!(
+        custom::Transcriber::from_toml(&unordered),
+        Err(custom::Error::StageOrder)
+    ));
+    assert!(!pipeline::in_order(&[Stage::Rules, Stage::Exceptions]));
+
+    // Dictionaries found before normalization would land on other words
+    let unordered = RULE_FILE.replace(
+        r#"["normalize", "rules", "post-rules"]"#,
+        r#"["exceptions", "normalize", "rules", "casing"]"#,
+    );
+    assert!(matches!(
+        custom::Transcriber::from_toml(&unordered),
+        Err(custom::Error::StageOrder)
+    ));
+    let custom = custom::Transcriber::from_toml(RULE_FILE).unwrap();
+    let stages = &[Stage::Normalize, Stage::Exceptions, Stage::Rules];
+    let custom = custom.with_stages(stages).unwrap();
+    assert_eq!(custom.transcribe("\u{200B}Ia Ia"), "Я Я");
+}
+
+#[test]
+fn test_spelling() {
+    let warnings = spelling::check_text("Ьен Оъ Ыт Массса Тайй Ан-Ьен");
+    let words: Vec<_> = warnings.iter().map(|w| w.to_string()).collect();
+    assert_eq!(
+        words,
+        [
+            "'Ьен' starts with a sign",
+            "'Оъ' has a sign after a vowel",
+            "'Ыт' starts with 'Ы'",
+            "'Массса' has a consonant tripled",
+            "'Тайй' has 'ЙЙ'",
+            "'Ьен' starts with a sign",
+        ]
+    );
+    // Only the same consonant tripled, runs of different ones are Russian
+    assert!(spelling::check_text("Нгуен Линь Мстислав Вздор").is_empty());
+
+    // Latin copied into a Cyrillic word, not Cyrillic passthrough
+    let vietnamese = TranscriberEnum::find("vie").unwrap();
+    let (output, warnings) = vietnamese.transcribe_checked("Nguyễn Wu");
+    assert_eq!(output, "Нгуен Wу");
+    assert_eq!(
+        warnings,
+        [spelling::Warning::MixedScript {
+            word: "Wу".to_string(),
+            copied: "W".to_string(),
+        }]
+    );
+    assert!(vietnamese.transcribe_checked("Нгуен W").1.is_empty());
+
+    // Output as respelled by the spelling conventions is checked
+    let overrides = Overrides::default();
+    let orthography = Orthography {
+        replacements: vec![("Л".to_string(), "ЛЛЛ".to_string())],
+        ..Orthography::default()
+    };
+    let trace = vietnamese.trace_in("Linh", &overrides, &orthography);
+    assert_eq!(
+        spelling::check(&trace),
+        [spelling::Warning::TripleConsonant {
+            word: "Лллинь".to_string()
+        }]
+    );
+}
+
+// Minimized failures of the properties below
+#[test]
+fn test_prop_regressions() {
+    assert_eq!(casing::record_case(""), Case::Lower);
+    assert_eq!(casing::reapply_case("ᾨ", Case::Title), "Ὠι");
+    for transcriber in TranscriberEnum::iter() {
+        assert_eq!(transcriber.transcribe("А АЁё"), "А АЁё");
+        assert_eq!(
+            transcriber.transcribe("J"),
+            casing::reapply_case(&transcriber.transcribe("j"), Case::Title)
+        );
+        assert_eq!(transcriber.transcribe("\u{301}"), "\u{301}");
+    }
+}
+
+// Latin words joined by separators (no dictionary entry is likely to match)
+const NAME: &str = "[A-Za-z]{1,8}([ ,.()/0-9-]{1,3}[A-Za-z]{1,8}){0,3}";
+const CYRILLIC: &str = "[А-Яа-яЁё]{1,8}( [А-Яа-яЁё]{1,8}){0,3}";
+
+proptest! {
+    #[test]
+    fn test_prop_no_panic(
+        input in prop_oneof![
+            any::<String>(),
+            // Lone combining marks, invisible characters and apostrophes
+            "[a-zA-Zà-ỹ\u{300}-\u{36F}\u{200B}-\u{200F}\u{FEFF} '’-]{0,12}",
+        ],
+    ) {
+        for transcriber in TranscriberEnum::iter() {
+            let trace = transcriber.trace(&input);
+            prop_assert_eq!(trace.output, transcriber.transcribe(&input));
+            transcriber.transcribe_variants(&input, 4);
+        }
+    }
+
+    #[test]
+    fn test_prop_separators(input in NAME) {
+        let separators =
+            |text: &str| text.replace(|c: char| c.is_alphabetic(), "");
+        for transcriber in TranscriberEnum::iter() {
+            let output = transcriber.transcribe(&input);
+            prop_assert_eq!(separators(&output), separators(&input));
+        }
+    }
+
+    // Clean input is left as normalization grapheme by grapheme leaves it
+    #[test]
+    fn test_prop_normalize(
+        input in "[a-zà-ÿƠ-ưẠ-ỹ\u{300}-\u{36F}\u{200B}\u{FEFF} '’]{0,12}",
+    ) {
+        if normalize::is_normalized(&input) {
+            // Invisible character forces the grapheme by grapheme path
+            let marked = format!("{input}\u{200B}");
+            prop_assert_eq!(normalize::normalize(&marked).text, input);
+        }
+    }
+
+    #[test]
+    fn test_prop_cyrillic(input in CYRILLIC) {
+        for transcriber in TranscriberEnum::iter() {
+            prop_assert_eq!(transcriber.transcribe(&input), input.as_str());
+        }
+    }
+
+    // Single capitals are initials, i.e. title case
+    #[test]
+    fn test_prop_case(input in "[a-z]{2,8}( [a-z]{2,8}){0,3}") {
+        let title = input
+            .split(' ')
+            .map(|word| casing::reapply_case(word, Case::Title))
+            .collect::<Vec<_>>()
+            .join(" ");
+        for transcriber in TranscriberEnum::iter() {
+            // Output words follow the case of input words
+            let lower = transcriber.transcribe(&input);
+            prop_assert_eq!(&lower, &lower.to_lowercase());
+            let upper = transcriber.transcribe(&input.to_uppercase());
+            prop_assert_eq!(&upper, &lower.to_uppercase());
+            let titled = transcriber.transcribe(&title);
+            for (word, lower) in titled.split(' ').zip(lower.split(' ')) {
+                prop_assert_eq!(
+                    word,
+                    casing::reapply_case(lower, Case::Title)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_prop_record_case(
+        word in "[A-Za-zА-Яа-я]{2,8}",
+        any_word in "\\PC{0,8}",
+    ) {
+        // Recorded case is the reapplied one
+        for case in [Case::Lower, Case::Upper, Case::Title] {
+            let recased = casing::reapply_case(&word, case);
+            prop_assert_eq!(casing::record_case(&recased), case);
+        }
+        let case = casing::record_case(&any_word);
+        if case != Case::Mixed {
+            let recased = casing::reapply_case(&any_word, case);
+            prop_assert_eq!(casing::record_case(&recased), case);
+        }
     }
 }
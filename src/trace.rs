@@ -0,0 +1,95 @@
+use std::ops::Range;
+
+use crate::casing::Case;
+use crate::dictionary::Entry;
+use crate::transcriber::Rule;
+
+/// What produced an output segment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Origin {
+    /// Rule of the language table
+    Rule(&'static Rule),
+    /// User override of the language
+    Override,
+    /// Whole-name exception of the language
+    Exception(&'static Entry),
+    /// Grapheme without rule, copied as-is
+    Unmatched,
+    /// Non-alphabetic text between words, copied as-is
+    Separator,
+}
+
+/// Source span transcribed into target span of the result
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Byte span in the input
+    pub source: Range<usize>,
+    /// Byte span in the output
+    pub target: Range<usize>,
+    pub origin: Origin,
+    /// Case applied to the fragment (none for verbatim text and dictionaries)
+    pub case: Option<Case>,
+}
+
+impl Segment {
+    /// Rule key (including `|` anchors and context) if rule matched
+    pub fn rule_key(&self) -> Option<&'static str> {
+        match self.origin {
+            Origin::Rule(rule) => Some(rule.key),
+            _ => None,
+        }
+    }
+}
+
+/// Transcription result with the segment by segment breakdown
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trace {
+    pub output: String,
+    pub segments: Vec<Segment>,
+    /// Post rules applied to the output, in output order
+    pub post_rules: Vec<&'static Rule>,
+}
+
+impl Trace {
+    /// Output fragment of the segment
+    pub fn fragment(&self, segment: &Segment) -> &str {
+        &self.output[segment.target.clone()]
+    }
+
+    /// Segments produced by rules
+    pub fn matched(&self) -> impl Iterator<Item = &Segment> {
+        self.segments
+            .iter()
+            .filter(|segment| matches!(segment.origin, Origin::Rule(_)))
+    }
+
+    /// Checks if any user override was applied
+    pub fn overridden(&self) -> bool {
+        self.segments.iter().any(|segment| segment.origin == Origin::Override)
+    }
+
+    /// Segments copied as-is inside words
+    pub fn unmatched(&self) -> impl Iterator<Item = &Segment> {
+        self.segments
+            .iter()
+            .filter(|segment| segment.origin == Origin::Unmatched)
+    }
+
+    /// Appends output fragment for the source span
+    pub fn push(
+        &mut self,
+        source: Range<usize>,
+        text: &str,
+        origin: Origin,
+        case: Option<Case>,
+    ) {
+        let start = self.output.len();
+        self.output.push_str(text);
+        self.segments.push(Segment {
+            source,
+            target: start..self.output.len(),
+            origin,
+            case,
+        });
+    }
+}
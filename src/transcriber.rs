@@ -1,25 +1,91 @@
 use enum_dispatch::enum_dispatch;
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::casing;
+use crate::custom;
+use crate::dictionary::Entry;
 use crate::langs::{bur, tha, vie};
-
-// Enum language
+use crate::name::Convention;
+use crate::orthography::Orthography;
+use crate::overrides::Overrides;
+use crate::pipeline::{self, Stage};
+use crate::reverse::{self, Candidate};
+use crate::spelling::{self, Warning};
+use crate::stress;
+use crate::trace::Trace;
+use crate::trie::Trie;
+use crate::variants::{self, Variant};
+
+// Enum language (custom transcribers are few, their size does not matter)
+#[allow(clippy::large_enum_variant)]
 #[derive(EnumIter)]
 #[enum_dispatch(TranscriberTrait)]
 pub enum TranscriberEnum {
     Thai(tha::Transcriber),
     Burmese(bur::Transcriber),
     Vietnamese(vie::Transcriber),
+    // Loaded from rule files at runtime (see `Registry`)
+    #[strum(disabled)]
+    Custom(custom::Transcriber),
+}
+
+impl TranscriberEnum {
+    /// Finds transcriber by language name or code (case insensitive)
+    pub fn find(query: &str) -> Option<Self> {
+        Self::iter().find(|transcriber| {
+            transcriber.get_lang_name() == query
+                || transcriber.get_lang_code().eq_ignore_ascii_case(query)
+        })
+    }
+
+    /// Lists names of all available languages
+    pub fn lang_names() -> impl Iterator<Item = &'static str> {
+        Self::iter().map(|transcriber| transcriber.get_lang_name())
+    }
+}
+
+// Test mapping (from -> to)
+#[derive(Debug, Clone, Copy)]
+pub struct Mapping<'a> {
+    pub from: &'a str,
+    pub to: &'a str,
+}
+
+// Rule definition (`|` in key anchors it to the word start or end)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rule {
+    pub key: &'static str,
+    pub value: &'static str,
+    // Less systematic readings (established forms, imprecise choices)
+    pub alternatives: &'static [&'static str],
+    // Section of the table the rule belongs to
+    pub section: &'static str,
 }
 
-// Rules definition
-#[derive(Copy, Clone)]
-pub struct SearchRules {
-    pub max_key_len: usize,
-    pub prefix_start: bool,
-    pub postfix_end: bool,
+impl Rule {
+    pub const fn new(key: &'static str, value: &'static str) -> Self {
+        Self {
+            key,
+            value,
+            alternatives: &[],
+            section: "",
+        }
+    }
+
+    /// Matched text without context and `|` anchors, and anchor flags
+    pub const fn pattern(&self) -> (&'static str, bool, bool) {
+        let mut body = crate::context::split(self.key).0;
+        let start = !body.is_empty() && body.as_bytes()[0] == b'|';
+        if start {
+            body = body.split_at(1).1;
+        }
+        let end = !body.is_empty() && body.as_bytes()[body.len() - 1] == b'|';
+        if end {
+            body = body.split_at(body.len() - 1).0;
+        }
+        (body, start, end)
+    }
 }
 
 // Generic language transcriber engine
@@ -27,234 +93,248 @@ pub struct SearchRules {
 pub trait TranscriberTrait {
     fn get_lang_name(&self) -> &'static str;
 
-    fn get_search_rules(&self) -> SearchRules;
-
-    fn get_transcription(&self, key: &str) -> Option<&'static str>;
-
-    #[cfg(test)]
-    fn get_mappings(&self) -> Vec<crate::tests::Mapping>;
+    fn get_lang_code(&self) -> &'static str;
 
-    // -- DEFAULT IMPLEMENTATION --
+    fn get_rules(&self) -> &'static [Rule];
 
-    fn transcribe_word_segment(
-        &self,
-        key: &str,
-        prefix_start: bool,
-        postfix_end: bool,
-    ) -> Option<&'static str> {
-        // Format key if needed
-        if prefix_start || postfix_end {
-            let formatted_key = if prefix_start && postfix_end {
-                format!("|{}|", key)
-            } else if prefix_start {
-                format!("|{}", key)
-            } else if postfix_end {
-                format!("{}|", key)
-            } else {
-                unreachable!();
-            };
+    fn get_trie(&self) -> Trie<'_>;
 
-            // Check formatted key
-            if let Some(transcription) = self.get_transcription(&formatted_key)
-            {
-                return Some(transcription);
-            }
-        }
+    fn get_post_rules(&self) -> &'static [Rule];
 
-        // Check original key (maybe as fallback)
-        self.get_transcription(key)
-    }
+    fn get_post_trie(&self) -> Trie<'_>;
 
-    fn transcribe_word(&self, input: &str) -> String {
-        let mut current = input;
-        let mut output = String::with_capacity(input.len() * 2);
-
-        let SearchRules {
-            max_key_len,      // Value defining the max grapheme slice length
-            mut prefix_start, // Rule applied only once (at the start)
-            postfix_end,      // Rule applied with extra condition (at the end)
-        } = self.get_search_rules();
-
-        while !current.is_empty() {
-            let mut matched = false;
-
-            // Collect next `max_key_len` graphemes
-            let graphemes: Vec<(usize, &str)> =
-                current.grapheme_indices(true).take(max_key_len).collect();
-
-            // Iterate over graphemes backwards
-            for i in (0..graphemes.len()).rev() {
-                // Slice from current to farther grapheme end (longest matching)
-                let (grapheme_start, grapheme) = graphemes[i];
-                let grapheme_end = grapheme_start + grapheme.len();
-                let segment = &current[..grapheme_end];
-
-                // Process the segment if transcription found
-                if let Some(transcription) = self.transcribe_word_segment(
-                    segment,
-                    prefix_start,
-                    // Optimization: Conditional postfix pattern formatting only at the end
-                    postfix_end && grapheme_end == current.len(),
-                ) {
-                    // Record && Advance && Flag
-                    output.push_str(transcription);
-                    current = &current[grapheme_end..];
-                    matched = true;
-                    // Optimization: After matching on start, no more prefix pattern formatting
-                    prefix_start = false;
-                    break;
-                }
-            }
+    fn get_exceptions(&self) -> &'static [Entry];
 
-            // On no match, copy as-is
-            if !matched {
-                if let Some(first_grapheme) = current.graphemes(true).next() {
-                    output.push_str(first_grapheme);
-                    current = &current[first_grapheme.len()..];
-                }
-            }
-        }
+    fn get_markers(&self) -> &'static [&'static str];
 
-        output
-    }
+    fn get_name_convention(&self) -> Convention;
 
-    /// Transcribes segment case sensitive
-    fn transcribe_segment(&self, segment: &str, result: &mut String) {
-        // -- SEGMENT HANDLING --
-        // Discard empty segment
-        if segment.is_empty() {
-            return;
-        }
+    fn get_stages(&self) -> &'static [Stage];
 
-        // Transcribe non-alphabetic segment
-        if !segment.chars().next().unwrap().is_alphabetic() {
-            result.push_str(segment);
-            return;
-        }
+    fn get_mappings(&self) -> Vec<Mapping<'_>>;
 
-        // -- WORD HANDLING --
-        let word = segment;
+    // -- DEFAULT IMPLEMENTATION --
 
-        // 1. Record original case
-        let case = casing::record_case(&word);
+    /// Transcribes input reporting the rule behind every output fragment
+    fn trace(&self, input: &str) -> Trace {
+        self.trace_with(input, &Overrides::default())
+    }
 
-        // 2. Transcribe uppercased segment
-        let word_upper = word.to_uppercase();
-        let word_transcribed = self.transcribe_word(&word_upper);
+    /// Traces input consulting user overrides of the language first
+    /// (see `pipeline` for the stages)
+    fn trace_with(&self, input: &str, overrides: &Overrides) -> Trace {
+        self.trace_in(input, overrides, &Orthography::default())
+    }
 
-        // 3. Reapply original case
-        let word_cased = casing::reapply_case(&word_transcribed, case);
-        result.push_str(&word_cased);
+    /// Traces input consulting user overrides, spelled in the conventions
+    /// given
+    fn trace_in(
+        &self,
+        input: &str,
+        overrides: &Overrides,
+        orthography: &Orthography,
+    ) -> Trace {
+        pipeline::run(self, input, overrides, orthography)
     }
 
-    /// Transcribe input: transcribe_segment -> transcribe_word
+    /// Transcribes input (as `trace`, without recording the trace)
     fn transcribe(&self, input: &str) -> String {
-        let mut result = String::with_capacity(input.len() * 2);
-        let mut last_boundary = 0;
-
-        // Define if first segment alphabetic
-        let mut last_was_alphabetic = input
-            .graphemes(true)
-            .next()
-            .map_or(false, |g| g.chars().next().unwrap().is_alphabetic());
-
-        // Transcribe segments based on boundaries
-        for (i, grapheme) in input.grapheme_indices(true) {
-            let current_is_alphabetic =
-                grapheme.chars().next().unwrap().is_alphabetic();
-
-            if current_is_alphabetic != last_was_alphabetic {
-                let segment = &input[last_boundary..i];
-                self.transcribe_segment(segment, &mut result);
-                last_boundary = i;
-                last_was_alphabetic = current_is_alphabetic;
-            }
-        }
+        self.transcribe_in(input, &Orthography::default())
+    }
 
-        // Transcribe segment after the boundary
-        self.transcribe_segment(&input[last_boundary..], &mut result);
+    /// Transcribes input along with the Russian spelling it breaks
+    fn transcribe_checked(&self, input: &str) -> (String, Vec<Warning>) {
+        let trace = self.trace(input);
+        let warnings = spelling::check(&trace);
+        (trace.output, warnings)
+    }
 
-        result
+    /// Transcribes input in the spelling conventions given
+    fn transcribe_in(&self, input: &str, orthography: &Orthography) -> String {
+        pipeline::transcribe(self, input, &Overrides::default(), orthography)
     }
-}
 
-// Compile time search rules derivation
-pub const fn derive_search_rules(
-    rules: &'static [(&str, &str)],
-) -> SearchRules {
-    let mut max_key_len = 0;
-    let mut prefix_start = false;
-    let mut postfix_end = false;
-
-    let mut i = 0;
-    while i < rules.len() {
-        let key = rules[i].0;
-        let mut len = key.len();
-
-        if key.as_bytes()[0] == b'|' {
-            prefix_start = true;
-            len -= 1;
-        }
-        if len > 0 && key.as_bytes()[key.len() - 1] == b'|' {
-            postfix_end = true;
-            len -= 1;
-        }
+    /// Transcribes input with stress marks where the language places them
+    fn transcribe_stressed(&self, input: &str) -> String {
+        let stress = self.get_name_convention().stress;
+        stress::stress(&self.trace(input), stress)
+    }
 
-        if len > max_key_len {
-            max_key_len = len;
-        }
-        i += 1;
+    /// Lists up to `limit` readings of input from rule alternatives,
+    /// ranked by the number of alternatives used (0 is the systematic one)
+    fn transcribe_variants(&self, input: &str, limit: usize) -> Vec<Variant> {
+        let (overrides, orthography) = Default::default();
+        variants::variants(self, input, &overrides, &orthography, limit)
     }
 
-    SearchRules {
-        max_key_len,
-        prefix_start,
-        postfix_end,
+    /// Enumerates up to `limit` source spellings of Cyrillic input,
+    /// the most likely first
+    fn reverse(&self, input: &str, limit: usize) -> Vec<Candidate> {
+        reverse::reverse(self, input, limit)
     }
 }
 
+/// Alphabetic and non-alphabetic runs of input with their offsets
+pub fn segments(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let is_alphabetic = |g: &str| g.chars().next().unwrap().is_alphabetic();
+    let mut graphemes = input.grapheme_indices(true).peekable();
+    std::iter::from_fn(move || {
+        let (start, first) = graphemes.next()?;
+        let is_word = is_alphabetic(first);
+        let mut end = start + first.len();
+        while let Some((i, g)) =
+            graphemes.next_if(|(_, g)| is_alphabetic(g) == is_word)
+        {
+            end = i + g.len();
+        }
+        Some((start, &input[start..end]))
+    })
+}
+
 #[macro_export]
 macro_rules! create_transcriber {
     (
         // Language name
         lang_name: $lang_name:literal,
-        // Language rules
-        lang_rules: { $($key:literal => $value:literal),* $(,)? },
+        // Language code (ISO 639)
+        lang_code: $lang_code:literal,
+        // Language rules by section (alternative readings after `|`)
+        lang_rules: {
+            $($section:literal {
+                $($key:literal => $value:literal $(| $alt:literal)*),* $(,)?
+            }),* $(,)?
+        },
+        // Rewrites of the uppercased Cyrillic output of a word, before its
+        // case is reapplied (`|` anchors as in keys)
+        $(post_rules: {
+            $($post_key:literal => $post_value:literal),* $(,)?
+        },)?
+        // Whole-name exceptions (traditional spellings)
+        $(exceptions: {
+            $($from:literal => $to:literal),* $(,)?
+        },)?
+        // Letter sequences typical of the language (`|` anchors as in keys)
+        $(markers: { $($marker:literal),* $(,)? },)?
+        // Naming convention: family name position, leading honorifics,
+        // parts telling the gender and stress placement
+        names: {
+            order: $order:ident,
+            honorifics: { $($honorific:literal),* $(,)? },
+            genders: { $($part:literal => $gender:ident),* $(,)? },
+            stress: $stress:ident $(,)?
+        },
+        // Pipeline stages (`pipeline::DEFAULT` if none)
+        $(stages: { $($stage:ident $(($stage_fn:expr))?),* $(,)? },)?
         // Mappings (from -> to)
         mappings: { $($mapping_body:tt)* }
     ) => {
         // Create character mappings from rule tokens
-        const LANG_RULES: &'static [(&'static str, &'static str)] = &[
-            $( ($key, $value) ),*
+        const LANG_RULES: &'static [$crate::transcriber::Rule] = &[
+            $($( $crate::transcriber::Rule {
+                key: $key,
+                value: $value,
+                alternatives: &[$($alt),*],
+                section: $section,
+            } ),*),*
+        ];
+
+        const POST_RULES: &'static [$crate::transcriber::Rule] = &[
+            $($( $crate::transcriber::Rule {
+                key: $post_key,
+                value: $post_value,
+                alternatives: &[],
+                section: "POST RULES",
+            } ),*)?
+        ];
+
+        const EXCEPTIONS: &'static [$crate::dictionary::Entry] = &[
+            $($( $crate::dictionary::Entry { from: $from, to: $to } ),*)?
         ];
 
+        const MARKERS: &'static [&'static str] = &[$($($marker),*)?];
+
+        const NAMES: $crate::name::Convention = $crate::name::Convention {
+            order: $crate::name::Order::$order,
+            honorifics: &[$($honorific),*],
+            genders: &[$(($part, $crate::name::Gender::$gender)),*],
+            stress: $crate::stress::Stress::$stress,
+        };
+
+        // Input stages must precede dictionaries and word stages
+        const STAGES: &'static [$crate::pipeline::Stage] = {
+            let stages: &'static [$crate::pipeline::Stage] = &[
+                $($($crate::pipeline::Stage::$stage $(($stage_fn))?),*)?
+            ];
+            assert!(
+                $crate::pipeline::in_order(stages),
+                "Stages out of order"
+            );
+            if stages.is_empty() {
+                $crate::pipeline::DEFAULT
+            } else {
+                stages
+            }
+        };
+
+        // Build rule trie at COMPILE TIME
+        static TRIE: $crate::trie::TrieTable<
+            { $crate::trie::capacity(LANG_RULES) },
+            { LANG_RULES.len() },
+        > = $crate::trie::TrieTable::new(LANG_RULES);
+
+        static POST_TRIE: $crate::trie::TrieTable<
+            { $crate::trie::capacity(POST_RULES) },
+            { POST_RULES.len() },
+        > = $crate::trie::TrieTable::new(POST_RULES);
+
         // Define transcriber struct
         #[derive(Default)]
         pub struct Transcriber;
 
         // Implement transcriber trait
-        impl crate::transcriber::TranscriberTrait for Transcriber {
+        impl $crate::transcriber::TranscriberTrait for Transcriber {
             fn get_lang_name(&self) -> &'static str {
                 $lang_name
             }
 
-            fn get_search_rules(&self) -> crate::transcriber::SearchRules {
-                // Derive search rules at COMPILE TIME
-                const SEARCH_RULES: crate::transcriber::SearchRules =
-                    crate::transcriber::derive_search_rules(&LANG_RULES);
-                SEARCH_RULES
+            fn get_lang_code(&self) -> &'static str {
+                $lang_code
             }
 
-            #[cfg(test)]
-            fn get_mappings(&self) -> Vec<crate::tests::Mapping> {
-                $($mapping_body)*
+            fn get_rules(&self) -> &'static [$crate::transcriber::Rule] {
+                LANG_RULES
             }
 
-            fn get_transcription(&self, key: &str) -> Option<&'static str> {
-                hashify::tiny_map! {
-                    key.as_bytes(),
-                    $($key => $value),*
-                }
+            fn get_trie(&self) -> $crate::trie::Trie<'_> {
+                TRIE.trie()
+            }
+
+            fn get_post_rules(&self) -> &'static [$crate::transcriber::Rule] {
+                POST_RULES
+            }
+
+            fn get_post_trie(&self) -> $crate::trie::Trie<'_> {
+                POST_TRIE.trie()
+            }
+
+            fn get_exceptions(&self) -> &'static [$crate::dictionary::Entry] {
+                EXCEPTIONS
+            }
+
+            fn get_markers(&self) -> &'static [&'static str] {
+                MARKERS
+            }
+
+            fn get_name_convention(&self) -> $crate::name::Convention {
+                NAMES
+            }
+
+            fn get_stages(&self) -> &'static [$crate::pipeline::Stage] {
+                STAGES
+            }
+
+            fn get_mappings(&self) -> Vec<$crate::transcriber::Mapping<'_>> {
+                $($mapping_body)*
             }
         }
     };
@@ -0,0 +1,259 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::context;
+use crate::transcriber::Rule;
+
+const NONE: u16 = u16::MAX;
+
+// Trie node (children and rules are singly linked lists)
+#[derive(Copy, Clone)]
+pub struct Node {
+    byte: u8,
+    child: u16,
+    sibling: u16,
+    rule: u16,
+}
+
+impl Node {
+    const EMPTY: Node = Node {
+        byte: 0,
+        child: NONE,
+        sibling: NONE,
+        rule: NONE,
+    };
+}
+
+// More anchors and conditions make the rule more specific
+const fn specificity(rule: &Rule) -> u8 {
+    let (_, start, end) = rule.pattern();
+    let conditional = context::split(rule.key).1.is_some();
+    start as u8 + end as u8 + conditional as u8
+}
+
+/// Node count needed for the rule table
+pub const fn capacity(rules: &[Rule]) -> usize {
+    assert!(fits(rules), "Rule table is too large");
+    nodes(rules)
+}
+
+/// Checks if rules and their nodes can be indexed by the trie
+pub const fn fits(rules: &[Rule]) -> bool {
+    rules.len() < NONE as usize && nodes(rules) < NONE as usize
+}
+
+const fn nodes(rules: &[Rule]) -> usize {
+    let mut nodes = 1;
+    let mut i = 0;
+    while i < rules.len() {
+        nodes += rules[i].pattern().0.len();
+        i += 1;
+    }
+    nodes
+}
+
+// Inserts rules into node and link slices sized by `capacity`
+const fn fill(rules: &[Rule], nodes: &mut [Node], links: &mut [u16]) {
+    let mut count = 1;
+
+    let mut i = 0;
+    while i < rules.len() {
+        let body = rules[i].pattern().0.as_bytes();
+
+        // Bare anchors would match empty input forever
+        if body.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        // Walk down the trie, creating missing nodes
+        let mut node = 0;
+        let mut j = 0;
+        while j < body.len() {
+            let mut child = nodes[node].child;
+            while child != NONE && nodes[child as usize].byte != body[j] {
+                child = nodes[child as usize].sibling;
+            }
+
+            if child == NONE {
+                child = count as u16;
+                nodes[count] = Node {
+                    byte: body[j],
+                    child: NONE,
+                    sibling: nodes[node].child,
+                    rule: NONE,
+                };
+                nodes[node].child = child;
+                count += 1;
+            }
+
+            node = child as usize;
+            j += 1;
+        }
+
+        // Keep rule chain ordered from the most specific rule
+        let rank = specificity(&rules[i]);
+        let head = nodes[node].rule;
+        if head == NONE || specificity(&rules[head as usize]) < rank {
+            links[i] = head;
+            nodes[node].rule = i as u16;
+        } else {
+            let mut prev = head as usize;
+            while links[prev] != NONE
+                && specificity(&rules[links[prev] as usize]) >= rank
+            {
+                prev = links[prev] as usize;
+            }
+            links[i] = links[prev];
+            links[prev] = i as u16;
+        }
+
+        i += 1;
+    }
+}
+
+/// Trie built at compile time from a static rule table
+pub struct TrieTable<const N: usize, const R: usize> {
+    rules: &'static [Rule],
+    nodes: [Node; N],
+    links: [u16; R],
+}
+
+impl<const N: usize, const R: usize> TrieTable<N, R> {
+    pub const fn new(rules: &'static [Rule]) -> Self {
+        let mut nodes = [Node::EMPTY; N];
+        let mut links = [NONE; R];
+        fill(rules, &mut nodes, &mut links);
+        Self {
+            rules,
+            nodes,
+            links,
+        }
+    }
+
+    pub fn trie(&self) -> Trie<'_> {
+        Trie {
+            rules: self.rules,
+            nodes: &self.nodes,
+            links: &self.links,
+        }
+    }
+}
+
+/// Trie built at runtime from a rule table
+pub struct TrieBuf {
+    rules: &'static [Rule],
+    nodes: Vec<Node>,
+    links: Vec<u16>,
+}
+
+impl TrieBuf {
+    /// Builds trie of rules (panics unless the table `fits`)
+    pub fn new(rules: &'static [Rule]) -> Self {
+        let mut nodes = vec![Node::EMPTY; capacity(rules)];
+        let mut links = vec![NONE; rules.len()];
+        fill(rules, &mut nodes, &mut links);
+        Self {
+            rules,
+            nodes,
+            links,
+        }
+    }
+
+    pub fn rules(&self) -> &'static [Rule] {
+        self.rules
+    }
+
+    pub fn trie(&self) -> Trie<'_> {
+        Trie {
+            rules: self.rules,
+            nodes: &self.nodes,
+            links: &self.links,
+        }
+    }
+}
+
+/// Longest match matcher over the rule table
+#[derive(Copy, Clone)]
+pub struct Trie<'a> {
+    rules: &'static [Rule],
+    nodes: &'a [Node],
+    links: &'a [u16],
+}
+
+impl Trie<'_> {
+    /// Checks if the trie has no rules to match
+    pub fn is_empty(&self) -> bool {
+        self.nodes[0].child == NONE
+    }
+
+    fn child(&self, node: usize, byte: u8) -> Option<usize> {
+        let mut child = self.nodes[node].child;
+        while child != NONE {
+            if self.nodes[child as usize].byte == byte {
+                return Some(child as usize);
+            }
+            child = self.nodes[child as usize].sibling;
+        }
+        None
+    }
+
+    // First rule of the node chain allowed at the word span
+    fn rule(
+        &self,
+        node: usize,
+        word: &str,
+        start: usize,
+        end: usize,
+    ) -> Option<usize> {
+        let mut rule = self.nodes[node].rule;
+        while rule != NONE {
+            let key = self.rules[rule as usize].key;
+            let (_, needs_start, needs_end) =
+                self.rules[rule as usize].pattern();
+            let anchors_ok = (start == 0 || !needs_start)
+                && (end == word.len() || !needs_end);
+            let context_ok = match context::split(key).1 {
+                Some(condition) => {
+                    context::matches(condition, word, start, end)
+                }
+                None => true,
+            };
+            if anchors_ok && context_ok {
+                return Some(rule as usize);
+            }
+            rule = self.links[rule as usize];
+        }
+        None
+    }
+
+    /// Finds the longest rule matching word at position in one forward walk.
+    /// Returns the match end and the rule; keys end on grapheme boundaries.
+    pub fn longest_match(
+        &self,
+        word: &str,
+        pos: usize,
+    ) -> Option<(usize, &'static Rule)> {
+        // Positions starting no key are rejected before finding graphemes
+        let first = word[pos..].chars().next()?;
+        let bytes = &word.as_bytes()[pos..pos + first.len_utf8()];
+        bytes.iter().try_fold(0, |node, &byte| self.child(node, byte))?;
+        let mut node = 0;
+        let mut best = None;
+
+        for (offset, grapheme) in word[pos..].grapheme_indices(true) {
+            for &byte in grapheme.as_bytes() {
+                match self.child(node, byte) {
+                    Some(child) => node = child,
+                    None => return best,
+                }
+            }
+
+            let end = pos + offset + grapheme.len();
+            if let Some(rule) = self.rule(node, word, pos, end) {
+                best = Some((end, &self.rules[rule]));
+            }
+        }
+
+        best
+    }
+}
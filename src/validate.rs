@@ -0,0 +1,200 @@
+//! Static checks of rule tables.
+//!
+//! Finds rules that conflict with or repeat each other, keys that can never
+//! match the words of the normalized uppercased input, malformed keys and
+//! non-Cyrillic readings. Post rules are checked the same way against the uppercased
+//! output.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use unicode_normalization::is_nfc;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::context;
+use crate::spelling::is_cyrillic;
+use crate::transcriber::{Rule, TranscriberTrait};
+
+/// Problem of a rule
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    /// Same key with a different reading
+    Conflict {
+        key: &'static str,
+        value: &'static str,
+        earlier: &'static str,
+    },
+    /// Same key with the same reading
+    Duplicate { key: &'static str },
+    /// Same body, anchors and context as an earlier key with the same
+    /// reading, written differently (the earlier one always matches first)
+    Shadowed {
+        key: &'static str,
+        earlier: &'static str,
+    },
+    /// Nothing to match besides anchors and context
+    Empty { key: &'static str },
+    /// Lowercase letters (input is matched uppercased)
+    Lowercase { key: &'static str },
+    /// Decomposed characters (input is matched in NFC)
+    NotNfc { key: &'static str },
+    /// Characters other than letters (input is matched word by word)
+    NotAlphabetic { key: &'static str },
+    /// `|` between letters
+    InnerAnchor { key: &'static str },
+    /// Unclosed `[` class or more than one `_` in context
+    MalformedContext { key: &'static str },
+    /// Reading with a character that is not Cyrillic
+    NonCyrillic {
+        key: &'static str,
+        value: &'static str,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::Conflict {
+                key,
+                value,
+                earlier,
+            } => write!(f, "'{key}' reads '{value}', earlier '{earlier}'"),
+            Issue::Duplicate { key } => write!(f, "'{key}' is defined twice"),
+            Issue::Shadowed { key, earlier } => {
+                write!(f, "'{key}' is shadowed by '{earlier}'")
+            }
+            Issue::Empty { key } => write!(f, "'{key}' matches nothing"),
+            Issue::Lowercase { key } => {
+                write!(f, "'{key}' has lowercase letters and never matches")
+            }
+            Issue::NotNfc { key } => {
+                write!(f, "'{key}' is not in NFC and never matches")
+            }
+            Issue::NotAlphabetic { key } => {
+                write!(f, "'{key}' has non-letters and never matches")
+            }
+            Issue::InnerAnchor { key } => {
+                write!(f, "'{key}' has `|` in the middle")
+            }
+            Issue::MalformedContext { key } => {
+                write!(f, "'{key}' has malformed context")
+            }
+            Issue::NonCyrillic { key, value } => {
+                write!(f, "'{key}' reads '{value}' with non-Cyrillic letters")
+            }
+        }
+    }
+}
+
+/// Checks rule table, issues in table order
+pub fn validate(rules: &'static [Rule]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut seen: HashMap<Signature, &'static Rule> = HashMap::new();
+
+    for rule in rules {
+        let key = rule.key;
+        let (body, context) = context::split(key);
+        let (inner, start, end) = rule.pattern();
+
+        // Equivalent keys: same letters, anchors and context (spacing in
+        // context aside)
+        let context_signature: String =
+            context.unwrap_or_default().split_whitespace().collect();
+        let signature = (inner, start, end, context_signature);
+        match seen.get(&signature) {
+            Some(earlier) if earlier.value != rule.value => {
+                issues.push(Issue::Conflict {
+                    key,
+                    value: rule.value,
+                    earlier: earlier.value,
+                });
+            }
+            Some(earlier) if earlier.key == key => {
+                issues.push(Issue::Duplicate { key });
+            }
+            Some(earlier) => {
+                issues.push(Issue::Shadowed {
+                    key,
+                    earlier: earlier.key,
+                });
+            }
+            None => {
+                seen.insert(signature, rule);
+            }
+        }
+
+        if inner.is_empty() {
+            issues.push(Issue::Empty { key });
+        }
+        if inner.contains('|') {
+            issues.push(Issue::InnerAnchor { key });
+        }
+        if key.chars().any(char::is_lowercase) {
+            issues.push(Issue::Lowercase { key });
+        }
+        if !is_nfc(body) {
+            issues.push(Issue::NotNfc { key });
+        }
+        if inner.graphemes(true).any(|g| g != "|" && !is_letter(g)) {
+            issues.push(Issue::NotAlphabetic { key });
+        }
+        if context.is_some_and(|context| !is_well_formed(context)) {
+            issues.push(Issue::MalformedContext { key });
+        }
+
+        let readings = std::iter::once(&rule.value).chain(rule.alternatives);
+        for &value in readings {
+            if !value.chars().all(is_cyrillic) {
+                issues.push(Issue::NonCyrillic { key, value });
+            }
+        }
+    }
+
+    issues
+}
+
+/// Report of the issues of transcriber rules and post rules (empty if
+/// none)
+pub fn report<T: TranscriberTrait + ?Sized>(transcriber: &T) -> String {
+    let issues = validate(transcriber.get_rules());
+    let post_issues = validate(transcriber.get_post_rules());
+    if issues.is_empty() && post_issues.is_empty() {
+        return String::new();
+    }
+
+    let mut report = format!(
+        "{} ({}): {} issue(s)\n",
+        transcriber.get_lang_name(),
+        transcriber.get_lang_code(),
+        issues.len() + post_issues.len(),
+    );
+    for issue in issues {
+        report += &format!("  {issue}\n");
+    }
+    for issue in post_issues {
+        report += &format!("  post rules: {issue}\n");
+    }
+    report
+}
+
+// Body, anchors at the start and the end, and context without spaces
+type Signature = (&'static str, bool, bool, String);
+
+// Grapheme that words are made of (as `transcriber::segments` splits them)
+fn is_letter(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(char::is_alphabetic)
+}
+
+fn is_well_formed(context: &str) -> bool {
+    let mut open = false;
+    for c in context.chars() {
+        match c {
+            '[' if open => return false,
+            '[' => open = true,
+            ']' if open => open = false,
+            ']' => return false,
+            _ => {}
+        }
+    }
+    !open && context.matches('_').count() <= 1
+}
@@ -0,0 +1,103 @@
+use crate::orthography::Orthography;
+use crate::overrides::Overrides;
+use crate::pipeline;
+use crate::trace::Origin;
+use crate::transcriber::TranscriberTrait;
+
+/// Reading of input (rank is the number of alternatives used)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub text: String,
+    pub rank: usize,
+}
+
+/// Lists up to `limit` distinct readings, the systematic one first (every
+/// reading goes through all the stages of the language, post rules and
+/// spelling conventions included)
+pub fn variants<T: TranscriberTrait + ?Sized>(
+    transcriber: &T,
+    input: &str,
+    overrides: &Overrides,
+    orthography: &Orthography,
+    limit: usize,
+) -> Vec<Variant> {
+    // Number of alternative readings of every rule that has them
+    let trace = pipeline::run(transcriber, input, overrides, orthography);
+    let choices: Vec<usize> = trace
+        .segments
+        .iter()
+        .filter_map(|segment| match segment.origin {
+            Origin::Rule(rule) if !rule.alternatives.is_empty() => {
+                Some(rule.alternatives.len())
+            }
+            _ => None,
+        })
+        .collect();
+    let render = |picks: &[Option<usize>]| {
+        if picks.iter().all(Option::is_none) {
+            return trace.output.clone();
+        }
+        pipeline::transcribe_picked(
+            transcriber,
+            input,
+            overrides,
+            orthography,
+            picks,
+        )
+    };
+
+    let mut variants = Vec::new();
+    let mut picks = vec![None; choices.len()];
+    for rank in 0..=choices.len() {
+        let mut search = Search {
+            render: &render,
+            choices: &choices,
+            limit,
+            variants: &mut variants,
+        };
+        search.collect(&mut picks, 0, rank);
+        if variants.len() >= limit {
+            break;
+        }
+    }
+    variants
+}
+
+struct Search<'a> {
+    render: &'a dyn Fn(&[Option<usize>]) -> String,
+    // Number of alternatives of every choice
+    choices: &'a [usize],
+    limit: usize,
+    variants: &'a mut Vec<Variant>,
+}
+
+impl Search<'_> {
+    // Picks exactly `rank` more alternatives among choices from `from` on
+    fn collect(
+        &mut self,
+        picks: &mut [Option<usize>],
+        from: usize,
+        rank: usize,
+    ) {
+        if self.variants.len() >= self.limit {
+            return;
+        }
+
+        if rank == 0 {
+            let text = (self.render)(picks);
+            if !self.variants.iter().any(|variant| variant.text == text) {
+                let rank = picks.iter().flatten().count();
+                self.variants.push(Variant { text, rank });
+            }
+            return;
+        }
+
+        for i in from..self.choices.len() {
+            for reading in 0..self.choices[i] {
+                picks[i] = Some(reading);
+                self.collect(picks, i + 1, rank - 1);
+            }
+            picks[i] = None;
+        }
+    }
+}